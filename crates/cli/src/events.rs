@@ -0,0 +1,54 @@
+//! HTTP endpoint delivering events to workflows waiting with `event::wait`.
+
+use axum::{
+	Json,
+	body::Bytes,
+	extract::Path,
+	http::StatusCode,
+	response::{IntoResponse, Response},
+	routing::post,
+};
+use log::{error, info};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use interpreter::Value;
+
+/// Serves `POST /events/<name>` with an optional JSON payload as body.
+pub async fn serve_events(addr: String) {
+	let listener = match TcpListener::bind(&addr).await {
+		Ok(listener) => listener,
+		Err(err) => {
+			error!("Failed to listen for events on `{}`: {}!", addr, err);
+			return;
+		}
+	};
+	info!("Listening for events on `{}`", addr);
+
+	let app = axum::Router::new().route("/events/{name}", post(event));
+	if let Err(err) = axum::serve(listener, app).await {
+		error!("Event endpoint stopped: {}!", err);
+	}
+}
+
+async fn event(Path(name): Path<String>, body: Bytes) -> Response {
+	let payload = if body.is_empty() {
+		Value::Null
+	} else {
+		match serde_json::from_slice(&body) {
+			Ok(payload) => payload,
+			Err(err) => {
+				return (StatusCode::BAD_REQUEST, format!("Invalid payload: {}", err))
+					.into_response();
+			}
+		}
+	};
+
+	let delivered = interpreter::send_event(&name, payload).await;
+	info!(
+		"Event `{}` delivered to {} waiting call(s)",
+		name, delivered
+	);
+
+	Json(json!({ "delivered": delivered })).into_response()
+}
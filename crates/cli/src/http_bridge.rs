@@ -0,0 +1,90 @@
+//! Serves the router emulator and simulator over the plain HTTP API.
+
+use tokio_stream::StreamExt;
+
+use router::{
+	ActionRequest, HttpRouter, HttpRouterRequest, RouterCapabilities, RouterError, RouterHealth,
+	RouterResponse, Vehicle, VehicleSelector,
+	proto::{
+		CancelRequest, CapabilitiesRequest, HealthRequest, ReleaseRequest, ReserveRequest,
+		router_server, router_update::Update,
+	},
+};
+
+/// Forwards HTTP requests to a gRPC router implementation.
+pub struct HttpBridge<T> {
+	inner: T,
+}
+
+impl<T> HttpBridge<T> {
+	pub fn new(inner: T) -> Self {
+		Self { inner }
+	}
+}
+
+impl<T: router_server::Router> HttpRouter for HttpBridge<T> {
+	async fn execute(&self, request: HttpRouterRequest) -> Result<RouterResponse, RouterError> {
+		let mut action = ActionRequest::new(request.action, request.target);
+		action.params = request.params;
+		action.vehicle = request.vehicle;
+
+		let updates = self
+			.inner
+			.execute(tonic::Request::new(action.into_proto(request.id)))
+			.await?
+			.into_inner();
+		let mut updates = Box::pin(updates);
+		while let Some(update) = updates.next().await {
+			if let Some(Update::Response(response)) = update?.update {
+				return response.try_into();
+			}
+		}
+
+		Err(RouterError::Protocol(
+			"update stream closed without response".to_owned(),
+		))
+	}
+
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		self.inner
+			.cancel(tonic::Request::new(CancelRequest { action_id }))
+			.await?;
+
+		Ok(())
+	}
+
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		let request = ReserveRequest {
+			selector: Some(selector.into()),
+		};
+		let vehicle = self.inner.reserve(tonic::Request::new(request)).await?;
+
+		Ok(vehicle.into_inner().into())
+	}
+
+	async fn release(&self, vehicle: String) -> Result<(), RouterError> {
+		self.inner
+			.release(tonic::Request::new(ReleaseRequest { vehicle }))
+			.await?;
+
+		Ok(())
+	}
+
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
+		let health = self
+			.inner
+			.health(tonic::Request::new(HealthRequest {}))
+			.await?;
+
+		Ok(health.into_inner().into())
+	}
+
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
+		let capabilities = self
+			.inner
+			.capabilities(tonic::Request::new(CapabilitiesRequest {}))
+			.await?;
+
+		Ok(capabilities.into_inner().into())
+	}
+}
@@ -1,4 +1,5 @@
-//! With this CLI, you can check and run workflows and emulate the router.
+//! With this CLI, you can check, run and schedule workflows, emulate the router
+//! and simulate a VDA 5050 vehicle.
 
 use std::process::ExitCode;
 use std::{collections::HashMap, error::Error};
@@ -9,13 +10,23 @@ use simplelog::{ColorChoice, Config, TermLogger, TerminalMode}; // cspell:disabl
 use tokio::fs::read_to_string;
 use tokio::sync::mpsc;
 
-use ::router::RouterClientGrpc;
-use ast::Identifier;
-use format::{ColorMode, format_parser_error};
-use interpreter::LogEntry;
+use ::router::{RouterClientGrpc, RouterClientHttp, RouterClientVda5050, Vda5050Config};
+use ast::{Identifier, Workflow};
+use format::{ColorMode, format_parser_error, format_span};
+use interpreter::{LogEntry, RunOptions, Value};
+use site::Site;
 
+mod events;
+use events::serve_events;
+mod http_bridge;
 mod router;
-use router::router;
+use router::{parse_vehicle, router};
+mod scheduler;
+use scheduler::{SystemClock, scheduler};
+mod simulator;
+use simulator::{parse_speed, simulator};
+mod vehicle;
+use vehicle::{parse_broker, vehicle};
 
 #[derive(Debug, Parser)]
 enum Cli {
@@ -23,11 +34,204 @@ enum Cli {
 	Run {
 		file: String,
 		variables: Vec<String>,
+		#[clap(flatten)]
+		router: RouterArgs,
+		#[clap(
+			long,
+			value_name = "FILE",
+			help = "Site map to check targets against and to use in the `site` module"
+		)]
+		site: Option<String>,
+		#[clap(
+			long,
+			help = "Start without checking that the router is reachable and ready"
+		)]
+		skip_health_check: bool,
+		#[clap(
+			long,
+			value_name = "HOST:PORT",
+			help = "Accept events for `event::wait` as `POST /events/<name>` requests"
+		)]
+		events: Option<String>,
+		#[clap(
+			long,
+			help = "Seed for the `random` and `uuid` modules to repeat the random values of a run"
+		)]
+		seed: Option<u64>,
+	},
+	#[clap(name = "schedule", about = "Run workflows at the times of a schedule")]
+	Schedule {
+		#[clap(help = "JSON file with the scheduled jobs")]
+		file: String,
+		#[clap(flatten)]
+		router: RouterArgs,
+		#[clap(
+			long,
+			value_name = "FILE",
+			help = "Site map to check targets against and to use in the `site` module"
+		)]
+		site: Option<String>,
+		#[clap(
+			long,
+			help = "Start the runs without checking that the router is reachable and ready"
+		)]
+		skip_health_check: bool,
+		#[clap(
+			long,
+			value_name = "FILE",
+			help = "Append the outcome of each run as JSON line to the given file"
+		)]
+		history: Option<String>,
 	},
 	#[clap(name = "check", about = "Check the program")]
-	Check { file: String },
+	Check {
+		file: String,
+		#[clap(
+			long,
+			value_name = "FILE",
+			help = "Site map to warn about unknown stations and station areas in literal targets"
+		)]
+		site: Option<String>,
+	},
 	#[clap(name = "router", about = "Emulate the router")]
-	Router,
+	Router {
+		#[clap(long, help = "Emit fake progress updates while executing actions")]
+		progress: bool,
+		#[clap(
+			long = "vehicle",
+			value_name = "ID[:TYPE[:CAPABILITY,...]]",
+			help = "Vehicle which can be reserved, can be given multiple times"
+		)]
+		vehicles: Vec<String>,
+		#[clap(
+			long,
+			value_name = "FILE",
+			help = "Simulate the vehicles on the site map instead of asking for each status"
+		)]
+		site: Option<String>,
+		#[clap(
+			long,
+			value_name = "UNITS/S",
+			default_value_t = 1.0,
+			value_parser = parse_speed,
+			help = "Speed of the simulated vehicles in units of the site map per second"
+		)]
+		speed: f64,
+		#[clap(
+			long,
+			value_name = "HOST:PORT",
+			help = "Serve the plain HTTP API on the given address instead of gRPC"
+		)]
+		http: Option<String>,
+		#[clap(
+			long,
+			requires = "site",
+			help = "Do not print the actions of the simulated vehicles, e.g. for automated tests"
+		)]
+		quiet: bool,
+	},
+	#[clap(name = "vehicle", about = "Simulate a VDA 5050 vehicle")]
+	Vehicle {
+		#[clap(
+			long,
+			value_name = "HOST:PORT",
+			default_value = "localhost:1883",
+			help = "MQTT broker to connect to"
+		)]
+		broker: String,
+		#[clap(flatten)]
+		vehicle: VehicleArgs,
+		#[clap(long, help = "Report all actions as failed")]
+		fail_actions: bool,
+	},
+}
+
+#[derive(Debug, clap::Args)]
+struct RouterArgs {
+	#[clap(
+		long,
+		value_name = "HOST:PORT",
+		help = "Control a VDA 5050 vehicle over the given MQTT broker instead of the router"
+	)]
+	vda5050: Option<String>,
+	#[clap(
+		long,
+		value_name = "URL",
+		conflicts_with = "vda5050",
+		help = "Send the actions as JSON to a router with a plain HTTP API"
+	)]
+	http: Option<String>,
+	#[clap(flatten)]
+	vehicle: VehicleArgs,
+}
+
+impl RouterArgs {
+	/// `None` is returned after logging if the broker is malformed.
+	fn into_config(self) -> Option<RouterConfig> {
+		match (self.vda5050, self.http) {
+			(Some(broker), _) => {
+				let Some(config) = self.vehicle.into_config(&broker) else {
+					error!(
+						"Broker malformed `{}`, expected format <host>:<port>!",
+						broker
+					);
+					return None;
+				};
+				Some(RouterConfig::Vda5050(config))
+			}
+			(None, Some(url)) => Some(RouterConfig::Http(url)),
+			(None, None) => Some(RouterConfig::Grpc),
+		}
+	}
+}
+
+/// Router to run workflows with, each run gets its own client.
+#[derive(Debug, Clone)]
+enum RouterConfig {
+	Grpc,
+	Http(String),
+	Vda5050(Vda5050Config),
+}
+
+impl RouterConfig {
+	fn client(&self) -> interpreter::Router {
+		match self {
+			RouterConfig::Grpc => interpreter::Router::Grpc(RouterClientGrpc::new()),
+			RouterConfig::Http(url) => interpreter::Router::Http(RouterClientHttp::new(url)),
+			RouterConfig::Vda5050(config) => {
+				interpreter::Router::Vda5050(Box::new(RouterClientVda5050::new(config.clone())))
+			}
+		}
+	}
+}
+
+#[derive(Debug, clap::Args)]
+struct VehicleArgs {
+	#[clap(
+		long,
+		default_value = "wdl",
+		help = "Manufacturer of the VDA 5050 vehicle"
+	)]
+	manufacturer: String,
+	#[clap(
+		long,
+		default_value = "sim",
+		help = "Serial number of the VDA 5050 vehicle"
+	)]
+	serial_number: String,
+}
+
+impl VehicleArgs {
+	fn into_config(self, broker: &str) -> Option<Vda5050Config> {
+		let (host, port) = parse_broker(broker)?;
+
+		Some(Vda5050Config::new(
+			host,
+			port,
+			self.manufacturer,
+			self.serial_number,
+		))
+	}
 }
 
 #[tokio::main]
@@ -40,13 +244,108 @@ async fn main() -> Result<ExitCode, Box<dyn Error>> {
 	)?;
 
 	match Cli::parse() {
-		Cli::Run { file, variables } => run(&file, variables).await,
-		Cli::Check { file } => check(&file).await,
-		Cli::Router => router().await,
+		Cli::Run {
+			file,
+			variables,
+			router,
+			site,
+			skip_health_check,
+			events,
+			seed,
+		} => {
+			if let Some(addr) = events {
+				tokio::spawn(serve_events(addr));
+			}
+			let Some(router) = router.into_config() else {
+				return Ok(ExitCode::FAILURE);
+			};
+			let site = match load_site(site.as_deref()).await {
+				Ok(site) => site,
+				Err(code) => return Ok(code),
+			};
+			let options = RunOptions {
+				site,
+				preflight: !skip_health_check,
+				seed,
+			};
+			run(&file, variables, router.client(), options).await
+		}
+		Cli::Schedule {
+			file,
+			router,
+			site,
+			skip_health_check,
+			history,
+		} => {
+			let Some(router) = router.into_config() else {
+				return Ok(ExitCode::FAILURE);
+			};
+			let site = match load_site(site.as_deref()).await {
+				Ok(site) => site,
+				Err(code) => return Ok(code),
+			};
+			let options = RunOptions {
+				site,
+				preflight: !skip_health_check,
+				..Default::default()
+			};
+			scheduler(&file, router, options, history, SystemClock).await
+		}
+		Cli::Check { file, site } => {
+			let site = match load_site(site.as_deref()).await {
+				Ok(site) => site,
+				Err(code) => return Ok(code),
+			};
+			check(&file, site).await
+		}
+		Cli::Router {
+			progress,
+			vehicles,
+			site,
+			speed,
+			http,
+			quiet,
+		} => {
+			let fleet = if vehicles.is_empty() {
+				vec![
+					parse_vehicle("agv1:forklift:lift"),
+					parse_vehicle("agv2:tugger:tow"),
+				]
+			} else {
+				vehicles
+					.iter()
+					.map(|vehicle| parse_vehicle(vehicle))
+					.collect()
+			};
+			match load_site(site.as_deref()).await {
+				Ok(Some(site)) => simulator(site, speed, progress, fleet, http, quiet).await,
+				Ok(None) => router(progress, fleet, http).await,
+				Err(code) => Ok(code),
+			}
+		}
+		Cli::Vehicle {
+			broker,
+			vehicle: args,
+			fail_actions,
+		} => {
+			let Some(config) = args.into_config(&broker) else {
+				error!(
+					"Broker malformed `{}`, expected format <host>:<port>!",
+					broker
+				);
+				return Ok(ExitCode::FAILURE);
+			};
+			vehicle(config, fail_actions).await
+		}
 	}
 }
 
-async fn run(file: &str, vars: Vec<String>) -> Result<ExitCode, Box<dyn Error>> {
+async fn run(
+	file: &str,
+	vars: Vec<String>,
+	router: interpreter::Router,
+	options: RunOptions,
+) -> Result<ExitCode, Box<dyn Error>> {
 	let mut variables = HashMap::new();
 	for var in vars {
 		let Some(parts) = var.split_once('=') else {
@@ -80,11 +379,33 @@ async fn run(file: &str, vars: Vec<String>) -> Result<ExitCode, Box<dyn Error>>
 		}
 	};
 
+	if let Err(error) = execute(workflow, variables, router, options, "").await {
+		log_interpreter_error(&error, &src_code);
+		return Ok(ExitCode::FAILURE);
+	}
+
+	Ok(ExitCode::SUCCESS)
+}
+
+/// Runs the workflow, its logs are written with the given prefix.
+async fn execute(
+	workflow: Workflow,
+	variables: HashMap<Identifier, Value>,
+	router: interpreter::Router,
+	options: RunOptions,
+	log_prefix: &str,
+) -> Result<(), interpreter::Error> {
 	let (user_log_sender, mut user_log_receiver) = mpsc::channel::<LogEntry>(10);
 
+	let log_prefix = log_prefix.to_owned();
 	let log_handle = tokio::spawn(async move {
 		while let Some(log) = user_log_receiver.recv().await {
-			let msg = format!("{}{}", if log.user { "[user]: " } else { "" }, log.msg);
+			let msg = format!(
+				"{}{}{}",
+				log_prefix,
+				if log.user { "[user]: " } else { "" },
+				log.msg
+			);
 			match log.level {
 				interpreter::LogEntryLevel::Error => error!("{}", msg),
 				interpreter::LogEntryLevel::Warn => warn!("{}", msg),
@@ -95,34 +416,57 @@ async fn run(file: &str, vars: Vec<String>) -> Result<ExitCode, Box<dyn Error>>
 		}
 	});
 
-	let ret = interpreter::run_workflow(
-		workflow,
-		variables,
-		interpreter::Router::Grpc(RouterClientGrpc),
-		user_log_sender,
-	)
-	.await;
+	let ret =
+		interpreter::run_workflow(workflow, variables, router, options, user_log_sender).await;
 
 	if let Err(err) = log_handle.await {
 		error!("Failed to wait for log receiver: `{}`!", err);
 	};
 
-	if let Err(error) = ret {
-		log_interpreter_error(&error, &src_code);
-		return Ok(ExitCode::FAILURE);
+	ret
+}
+
+async fn check(file: &str, site: Option<Site>) -> Result<ExitCode, Box<dyn Error>> {
+	let src_code = read_to_string(file).await?;
+	let workflow = match parser::get_ast(&src_code) {
+		Ok(wf) => wf,
+		Err(error) => {
+			log_parser_error(&error, &src_code);
+			return Ok(ExitCode::FAILURE);
+		}
+	};
+
+	if let Some(site) = site {
+		for warning in site::check_workflow(&workflow, &site) {
+			let span = &warning.span;
+			warn!(
+				"{}\n{}",
+				warning.msg,
+				format_span(&span.start, &span.end, &src_code, ColorMode::ANSI)
+			);
+		}
 	}
 
 	Ok(ExitCode::SUCCESS)
 }
 
-async fn check(file: &str) -> Result<ExitCode, Box<dyn Error>> {
-	let src_code = read_to_string(file).await?;
-	if let Err(error) = parser::get_ast(&src_code) {
-		log_parser_error(&error, &src_code);
-		return Ok(ExitCode::FAILURE);
+/// Reads the site map if a file is given, fails after logging if it cannot be read or is invalid.
+async fn load_site(file: Option<&str>) -> Result<Option<Site>, ExitCode> {
+	let Some(file) = file else {
+		return Ok(None);
 	};
 
-	Ok(ExitCode::SUCCESS)
+	let json = read_to_string(file).await.map_err(|err| {
+		error!("Cannot read site file `{}`, {}!", file, err);
+		ExitCode::FAILURE
+	})?;
+	match Site::from_json(&json) {
+		Ok(site) => Ok(Some(site)),
+		Err(err) => {
+			error!("Invalid site file `{}`, {}!", file, err);
+			Err(ExitCode::FAILURE)
+		}
+	}
 }
 
 fn log_interpreter_error(err: &interpreter::Error, src_code: &str) {
@@ -1,32 +1,93 @@
-//! Implementation of the router emulator over gRPC.
+//! Implementation of the router emulator over gRPC or plain HTTP.
 
 use std::{
+	collections::HashSet,
 	error::Error,
 	io::{self, BufRead},
 	process::ExitCode,
+	sync::Mutex,
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::Server;
 
 use router::{
-	Router, RouterServer, Target,
-	proto::{RouterRequest, RouterResponse},
+	ActionRequest, HttpRouterServer, Router, RouterCapabilities, RouterHealth, RouterServer,
+	Target, Vehicle, VehicleSelector,
+	proto::{
+		self, CancelRequest, CancelResponse, CapabilitiesRequest, CapabilitiesResponse,
+		ExecuteRequest, HealthRequest, HealthResponse, ReleaseRequest, ReleaseResponse,
+		ReserveRequest, RouterProgress, RouterRequest, RouterResponse, RouterUpdate,
+		router_update::Update,
+	},
 };
 
-pub async fn router() -> Result<ExitCode, Box<dyn Error>> {
-	let addr = router::URL.parse()?;
+use crate::http_bridge::HttpBridge;
 
-	let router = RouterService;
+/// Input to answer an action with a robot fault instead of a status.
+const ROBOT_FAULT: i32 = 9;
 
-	Server::builder()
-		.add_service(RouterServer::new(router))
-		.serve(addr)
-		.await?;
+pub type UpdateStream = ReceiverStream<Result<RouterUpdate, tonic::Status>>;
+
+pub async fn router(
+	progress: bool,
+	fleet: Vec<Vehicle>,
+	http: Option<String>,
+) -> Result<ExitCode, Box<dyn Error>> {
+	let router = RouterService {
+		progress,
+		fleet,
+		reserved: Mutex::new(HashSet::new()),
+	};
+
+	serve(router, http).await
+}
+
+/// Serves the router over gRPC or, if an address is given, over the plain HTTP API.
+pub async fn serve<T: Router>(router: T, http: Option<String>) -> Result<ExitCode, Box<dyn Error>> {
+	match http {
+		Some(addr) => {
+			HttpRouterServer::new(HttpBridge::new(router))
+				.serve(addr)
+				.await?
+		}
+		None => {
+			Server::builder()
+				.add_service(RouterServer::new(router))
+				.serve(router::URL.parse()?)
+				.await?
+		}
+	}
 
 	Ok(ExitCode::SUCCESS)
 }
 
-pub struct RouterService;
+pub struct RouterService {
+	/// Emit fake progress updates on streaming requests.
+	progress: bool,
+	/// Vehicles which can be reserved.
+	fleet: Vec<Vehicle>,
+	reserved: Mutex<HashSet<String>>,
+}
+
+/// Parses a vehicle given as `<id>[:<type>[:<capability>,...]]`.
+pub fn parse_vehicle(vehicle: &str) -> Vehicle {
+	let mut parts = vehicle.splitn(3, ':');
+
+	Vehicle {
+		id: parts.next().unwrap_or_default().to_owned(),
+		type_: parts
+			.next()
+			.filter(|type_| !type_.is_empty())
+			.map(str::to_owned),
+		capabilities: parts
+			.next()
+			.map(|capabilities| capabilities.split(',').map(str::to_owned).collect())
+			.unwrap_or_default(),
+	}
+}
 
 #[tonic::async_trait]
 impl Router for RouterService {
@@ -50,25 +111,299 @@ impl Router for RouterService {
 	) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
 		action("Drive to", request).await
 	}
+
+	type PickupStreamStream = UpdateStream;
+
+	async fn pickup_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::PickupStreamStream>, tonic::Status> {
+		action_stream("Pickup from", request, self.progress).await
+	}
+
+	type DropStreamStream = UpdateStream;
+
+	async fn drop_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::DropStreamStream>, tonic::Status> {
+		action_stream("Drop to", request, self.progress).await
+	}
+
+	type DriveStreamStream = UpdateStream;
+
+	async fn drive_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::DriveStreamStream>, tonic::Status> {
+		action_stream("Drive to", request, self.progress).await
+	}
+
+	type ExecuteStream = UpdateStream;
+
+	async fn execute(
+		&self,
+		request: tonic::Request<ExecuteRequest>,
+	) -> Result<tonic::Response<Self::ExecuteStream>, tonic::Status> {
+		let action_id = request.get_ref().action_id;
+		let action = ActionRequest::try_from(request.into_inner())
+			.map_err(|err| tonic::Status::invalid_argument(err.to_string()))?;
+
+		let mut action_txt = format!("Execute `{}`", action.name);
+		if let Some(target) = &action.target {
+			action_txt += &format!(" at target `{:?}`", target);
+		}
+		if !action.params.is_empty() {
+			action_txt += &format!(
+				" with params `{}`",
+				serde_json::Value::Object(action.params)
+			);
+		}
+
+		update_stream(
+			action_txt,
+			action_id,
+			action.target,
+			action.vehicle,
+			self.progress,
+		)
+		.await
+	}
+
+	async fn cancel(
+		&self,
+		request: tonic::Request<CancelRequest>,
+	) -> Result<tonic::Response<CancelResponse>, tonic::Status> {
+		eprintln!("Cancel action `{}`", request.get_ref().action_id);
+		eprintln!();
+
+		Ok(tonic::Response::new(CancelResponse {}))
+	}
+
+	async fn reserve(
+		&self,
+		request: tonic::Request<ReserveRequest>,
+	) -> Result<tonic::Response<proto::Vehicle>, tonic::Status> {
+		let selector = VehicleSelector::from(request.into_inner().selector.unwrap_or_default());
+
+		let mut reserved = self.reserved.lock().unwrap();
+		let Some(vehicle) = reserve_vehicle(&self.fleet, &mut reserved, &selector) else {
+			return Err(tonic::Status::resource_exhausted(
+				"No matching vehicle available",
+			));
+		};
+
+		Ok(tonic::Response::new(vehicle.into()))
+	}
+
+	async fn release(
+		&self,
+		request: tonic::Request<ReleaseRequest>,
+	) -> Result<tonic::Response<ReleaseResponse>, tonic::Status> {
+		let vehicle = request.into_inner().vehicle;
+		self.reserved.lock().unwrap().remove(&vehicle);
+
+		eprintln!("Release vehicle `{}`", vehicle);
+		eprintln!();
+
+		Ok(tonic::Response::new(ReleaseResponse {}))
+	}
+	async fn health(
+		&self,
+		_request: tonic::Request<HealthRequest>,
+	) -> Result<tonic::Response<HealthResponse>, tonic::Status> {
+		Ok(tonic::Response::new(RouterHealth::ready().into()))
+	}
+
+	async fn capabilities(
+		&self,
+		_request: tonic::Request<CapabilitiesRequest>,
+	) -> Result<tonic::Response<CapabilitiesResponse>, tonic::Status> {
+		let capabilities = RouterCapabilities {
+			actions: None,
+			statuses: router::RouterStatus::ALL.to_vec(),
+			progress: self.progress,
+			reservation: true,
+		};
+
+		Ok(tonic::Response::new(capabilities.into()))
+	}
+}
+
+/// Reserves the first vehicle of the fleet matching the selector which is not reserved yet.
+pub fn reserve_vehicle(
+	fleet: &[Vehicle],
+	reserved: &mut HashSet<String>,
+	selector: &VehicleSelector,
+) -> Option<Vehicle> {
+	let Some(vehicle) = fleet
+		.iter()
+		.find(|vehicle| !reserved.contains(&vehicle.id) && selector.matches(vehicle))
+	else {
+		eprintln!("No vehicle available for `{:?}`", selector);
+		eprintln!();
+		return None;
+	};
+	reserved.insert(vehicle.id.clone());
+
+	eprintln!("Reserve vehicle `{}`", vehicle.id);
+	eprintln!();
+
+	Some(vehicle.clone())
 }
 
 pub async fn action(
 	action_txt: &str,
 	request: tonic::Request<RouterRequest>,
 ) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
-	let target: Target = match request.get_ref().clone().target {
-		Some(t) => t.into(),
-		None => return Err(tonic::Status::invalid_argument("Target must not be None")),
+	let action_id = request.get_ref().action_id;
+	let vehicle = request.get_ref().vehicle.clone();
+	let Some(target) = get_target(request) else {
+		return Err(tonic::Status::invalid_argument("Target must not be None"));
 	};
-	eprintln!("{} target `{:?}`", action_txt, target);
+	eprintln!(
+		"{} target `{:?}` (action `{}`)",
+		action_txt, target, action_id
+	);
+
+	let started_at_ms = now_ms();
+	match read_status() {
+		Some(ROBOT_FAULT) => Err(tonic::Status::aborted("Robot fault reported by emulator")),
+		Some(status) => Ok(tonic::Response::new(fake_response(
+			status,
+			Some(&target),
+			vehicle,
+			started_at_ms,
+		))),
+		None => Err(tonic::Status::internal("Failed to read from stdin")),
+	}
+}
 
-	eprintln!("Enter: 0 for action done, 1 to trigger no station left");
-	let Some(input) = read_i32_stdin() else {
-		return Err(tonic::Status::internal("Failed to read from stdin"));
+pub async fn action_stream(
+	action_txt: &str,
+	request: tonic::Request<RouterRequest>,
+	progress: bool,
+) -> Result<tonic::Response<UpdateStream>, tonic::Status> {
+	let action_id = request.get_ref().action_id;
+	let vehicle = request.get_ref().vehicle.clone();
+	let Some(target) = get_target(request) else {
+		return Err(tonic::Status::invalid_argument("Target must not be None"));
 	};
+
+	update_stream(
+		format!("{} target `{:?}`", action_txt, target),
+		action_id,
+		Some(target),
+		vehicle,
+		progress,
+	)
+	.await
+}
+
+/// Answers the action with fake progress updates and the status read from stdin.
+pub async fn update_stream(
+	action_txt: String,
+	action_id: u64,
+	target: Option<Target>,
+	vehicle: Option<String>,
+	progress: bool,
+) -> Result<tonic::Response<UpdateStream>, tonic::Status> {
+	match &vehicle {
+		Some(vehicle) => eprintln!(
+			"{} by vehicle `{}` (action `{}`)",
+			action_txt, vehicle, action_id
+		),
+		None => eprintln!("{} (action `{}`)", action_txt, action_id),
+	}
+
+	let (tx, rx) = mpsc::channel(4);
+
+	tokio::spawn(async move {
+		let started_at_ms = now_ms();
+		if progress {
+			for step in 1..=4 {
+				tokio::time::sleep(Duration::from_millis(500)).await;
+
+				let update = RouterUpdate {
+					update: Some(Update::Progress(RouterProgress {
+						vehicle: Some(vehicle.clone().unwrap_or_else(|| "emulator".to_owned())),
+						eta_ms: Some(f64::from(4 - step) * 500.0),
+						position: None,
+						percent: Some(f64::from(step) * 25.0),
+					})),
+				};
+				if tx.send(Ok(update)).await.is_err() {
+					return;
+				}
+			}
+		}
+
+		let update = match tokio::task::spawn_blocking(read_status).await {
+			Ok(Some(ROBOT_FAULT)) => {
+				Err(tonic::Status::aborted("Robot fault reported by emulator"))
+			}
+			Ok(Some(status)) => Ok(RouterUpdate {
+				update: Some(Update::Response(fake_response(
+					status,
+					target.as_ref(),
+					vehicle,
+					started_at_ms,
+				))),
+			}),
+			Ok(None) => Err(tonic::Status::internal("Failed to read from stdin")),
+			Err(err) => Err(tonic::Status::internal(err.to_string())),
+		};
+		tx.send(update).await.ok();
+	});
+
+	Ok(tonic::Response::new(ReceiverStream::new(rx)))
+}
+
+fn get_target(request: tonic::Request<RouterRequest>) -> Option<Target> {
+	request.into_inner().target.map(Target::from)
+}
+
+/// Builds a response which reports the first station or coordinate
+/// of the target as the resolved one.
+fn fake_response(
+	status: i32,
+	target: Option<&Target>,
+	vehicle: Option<String>,
+	started_at_ms: f64,
+) -> RouterResponse {
+	RouterResponse {
+		status,
+		station: target
+			.and_then(|target| target.stations.as_ref())
+			.and_then(|stations| stations.first().cloned()),
+		coordinate: target
+			.and_then(|target| target.coordinates.as_ref())
+			.and_then(|coordinates| coordinates.first().cloned())
+			.map(|coordinate| coordinate.into()),
+		vehicle: Some(vehicle.unwrap_or_else(|| "emulator".to_owned())),
+		started_at_ms: Some(started_at_ms),
+		finished_at_ms: Some(now_ms()),
+	}
+}
+
+pub fn now_ms() -> f64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|duration| duration.as_millis() as f64)
+		.unwrap_or_default()
+}
+
+/// Asks the user for the status of the current action.
+fn read_status() -> Option<i32> {
+	eprintln!(
+		"Enter: 0 for action done, 1 no station left, 2 path blocked, 3 load handling failed,\n\
+		4 battery low, 5 emergency stop, 6 timeout or {} to report a robot fault",
+		ROBOT_FAULT
+	);
+	let input = read_i32_stdin();
 	eprintln!();
 
-	Ok(tonic::Response::new(RouterResponse { status: input }))
+	input
 }
 
 fn read_i32_stdin() -> Option<i32> {
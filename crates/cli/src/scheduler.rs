@@ -0,0 +1,635 @@
+//! Scheduler starting workflows at the times of cron-style schedules.
+
+use std::{
+	collections::HashMap,
+	error::Error,
+	path::{Path, PathBuf},
+	process::ExitCode,
+	sync::{
+		Arc,
+		atomic::{AtomicBool, Ordering},
+	},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::{
+	fs::{OpenOptions, read_to_string},
+	io::AsyncWriteExt,
+	sync::Semaphore,
+};
+
+use ast::Identifier;
+use format::ColorMode;
+use interpreter::{ErrorKind, RunOptions, Value};
+
+use crate::{RouterConfig, execute, log_interpreter_error, log_parser_error};
+
+/// Source of the current time, can be replaced to run schedules on a simulated clock.
+pub trait Clock: Clone + Send + Sync + 'static {
+	fn now(&self) -> DateTime<Utc>;
+
+	/// Returns once `now` has reached `time`.
+	fn sleep_until(&self, time: DateTime<Utc>) -> impl Future<Output = ()> + Send;
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn now(&self) -> DateTime<Utc> {
+		Utc::now()
+	}
+
+	async fn sleep_until(&self, time: DateTime<Utc>) {
+		// the system time may be adjusted while sleeping
+		while let Ok(duration) = (time - Utc::now()).to_std() {
+			if duration.is_zero() {
+				break;
+			}
+			tokio::time::sleep(duration).await;
+		}
+	}
+}
+
+/// Content of the schedule file, e.g.
+/// `{ "jobs": [{ "name": "replenishment", "cron": "0 * * * *", "file": "replenishment.wdl" }] }`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScheduleFile {
+	jobs: Vec<JobConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobConfig {
+	name: String,
+	/// Five fields for minute, hour, day of month, month and day of week.
+	cron: String,
+	/// Workflow file, relative to the schedule file.
+	file: String,
+	#[serde(default)]
+	variables: HashMap<String, Value>,
+	/// IANA name of the timezone the schedule is in, UTC by default.
+	timezone: Option<String>,
+	#[serde(default)]
+	overlap: Overlap,
+	/// Seed for the random values, so that all runs draw the same ones.
+	seed: Option<u64>,
+}
+
+/// What happens if a run is due while the previous one is still running.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Overlap {
+	/// The due run is skipped.
+	#[default]
+	Skip,
+	/// The due run starts after the previous one, further runs are skipped meanwhile.
+	Queue,
+	/// The runs execute concurrently.
+	Allow,
+}
+
+struct Job {
+	name: String,
+	cron: Cron,
+	timezone: Tz,
+	file: PathBuf,
+	variables: HashMap<Identifier, Value>,
+	overlap: Overlap,
+	seed: Option<u64>,
+	running: Arc<Semaphore>,
+	queued: AtomicBool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+	Succeeded,
+	Canceled,
+	Failed,
+	Skipped,
+}
+
+impl Outcome {
+	fn as_str(&self) -> &'static str {
+		match self {
+			Outcome::Succeeded => "succeeded",
+			Outcome::Canceled => "canceled",
+			Outcome::Failed => "failed",
+			Outcome::Skipped => "skipped",
+		}
+	}
+}
+
+/// Outcome of a scheduled run, written as JSON line to the history file.
+#[derive(Debug, Serialize)]
+struct RunRecord {
+	job: String,
+	scheduled: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	started: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	finished: Option<String>,
+	outcome: Outcome,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	message: Option<String>,
+}
+
+struct Scheduler<C: Clock> {
+	router: RouterConfig,
+	options: RunOptions,
+	history: Option<String>,
+	clock: C,
+}
+
+/// Runs until the process is stopped, fails if the schedule file or a scheduled workflow is invalid.
+pub async fn scheduler<C: Clock>(
+	file: &str,
+	router: RouterConfig,
+	options: RunOptions,
+	history: Option<String>,
+	clock: C,
+) -> Result<ExitCode, Box<dyn Error>> {
+	let Some(jobs) = load_jobs(file).await? else {
+		return Ok(ExitCode::FAILURE);
+	};
+	if jobs.is_empty() {
+		warn!("No jobs scheduled in `{}`!", file);
+		return Ok(ExitCode::SUCCESS);
+	}
+
+	let scheduler = Arc::new(Scheduler {
+		router,
+		options,
+		history,
+		clock,
+	});
+
+	let handles: Vec<_> = jobs
+		.into_iter()
+		.map(|job| tokio::spawn(scheduler.clone().schedule(Arc::new(job))))
+		.collect();
+	for handle in handles {
+		handle.await?;
+	}
+
+	Ok(ExitCode::SUCCESS)
+}
+
+/// Reads and checks all jobs, `None` is returned after logging if one is invalid.
+async fn load_jobs(file: &str) -> Result<Option<Vec<Job>>, Box<dyn Error>> {
+	let schedule: ScheduleFile = match serde_json::from_str(&read_to_string(file).await?) {
+		Ok(schedule) => schedule,
+		Err(err) => {
+			error!("Invalid schedule file `{}`, {}!", file, err);
+			return Ok(None);
+		}
+	};
+	let dir = Path::new(file).parent().unwrap_or(Path::new(""));
+
+	let mut jobs = Vec::new();
+	for config in schedule.jobs {
+		let cron = match Cron::new(&config.cron).parse() {
+			Ok(cron) => cron,
+			Err(err) => {
+				error!(
+					"Invalid cron expression `{}` of job `{}`, {}!",
+					config.cron, config.name, err
+				);
+				return Ok(None);
+			}
+		};
+		let timezone = match config.timezone.as_deref().map(str::parse).transpose() {
+			Ok(timezone) => timezone.unwrap_or(Tz::UTC),
+			Err(_) => {
+				error!(
+					"Unknown timezone `{}` of job `{}`!",
+					config.timezone.unwrap_or_default(),
+					config.name
+				);
+				return Ok(None);
+			}
+		};
+
+		let file = dir.join(&config.file);
+		let src_code = match read_to_string(&file).await {
+			Ok(src_code) => src_code,
+			Err(err) => {
+				error!(
+					"Cannot read workflow `{}` of job `{}`, {}!",
+					file.display(),
+					config.name,
+					err
+				);
+				return Ok(None);
+			}
+		};
+		if let Err(error) = parser::get_ast(&src_code) {
+			error!("Invalid workflow of job `{}`!", config.name);
+			log_parser_error(&error, &src_code);
+			return Ok(None);
+		}
+
+		jobs.push(Job {
+			name: config.name,
+			cron,
+			timezone,
+			file,
+			variables: config
+				.variables
+				.into_iter()
+				.map(|(id, val)| (Identifier { id }, val))
+				.collect(),
+			overlap: config.overlap,
+			seed: config.seed,
+			running: Arc::new(Semaphore::new(1)),
+			queued: AtomicBool::new(false),
+		});
+	}
+
+	Ok(Some(jobs))
+}
+
+impl<C: Clock> Scheduler<C> {
+	/// Runs missed while the scheduler is busy are not made up for.
+	async fn schedule(self: Arc<Self>, job: Arc<Job>) {
+		let mut after = self.clock.now();
+		loop {
+			let next = match job
+				.cron
+				.find_next_occurrence(&after.with_timezone(&job.timezone), false)
+			{
+				Ok(next) => next.with_timezone(&Utc),
+				Err(err) => {
+					error!("No further runs of job `{}`, {}!", job.name, err);
+					return;
+				}
+			};
+			info!("Next run of job `{}` at {}", job.name, timestamp(next));
+
+			self.clock.sleep_until(next).await;
+			self.clone().start(job.clone(), next).await;
+			after = next.max(self.clock.now());
+		}
+	}
+
+	/// Applies the overlap policy of the job before running it in the background.
+	async fn start(self: Arc<Self>, job: Arc<Job>, scheduled: DateTime<Utc>) {
+		match job.overlap {
+			Overlap::Allow => {
+				tokio::spawn(async move { self.run(&job, scheduled).await });
+			}
+			Overlap::Skip => match job.running.clone().try_acquire_owned() {
+				Ok(permit) => {
+					tokio::spawn(async move {
+						self.run(&job, scheduled).await;
+						drop(permit);
+					});
+				}
+				Err(_) => {
+					self.skip(&job, scheduled, "Previous run still running")
+						.await;
+				}
+			},
+			Overlap::Queue => {
+				if job.queued.swap(true, Ordering::SeqCst) {
+					self.skip(&job, scheduled, "Another run is already queued")
+						.await;
+					return;
+				}
+				tokio::spawn(async move {
+					let Ok(permit) = job.running.clone().acquire_owned().await else {
+						return;
+					};
+					job.queued.store(false, Ordering::SeqCst);
+					self.run(&job, scheduled).await;
+					drop(permit);
+				});
+			}
+		}
+	}
+
+	async fn run(&self, job: &Job, scheduled: DateTime<Utc>) {
+		let started = self.clock.now();
+		info!("Job `{}` started", job.name);
+
+		let (outcome, message) = match self.execute(job).await {
+			Ok(()) => (Outcome::Succeeded, None),
+			Err((outcome, message)) => (outcome, Some(message)),
+		};
+
+		self.record(RunRecord {
+			job: job.name.clone(),
+			scheduled: timestamp(scheduled),
+			started: Some(timestamp(started)),
+			finished: Some(timestamp(self.clock.now())),
+			outcome,
+			message,
+		})
+		.await;
+	}
+
+	/// The workflow file is read again for each run, so changes apply to the next run.
+	async fn execute(&self, job: &Job) -> Result<(), (Outcome, String)> {
+		let src_code = read_to_string(&job.file).await.map_err(|err| {
+			(
+				Outcome::Failed,
+				format!("Cannot read `{}`: {}", job.file.display(), err),
+			)
+		})?;
+		let workflow = parser::get_ast(&src_code).map_err(|error| {
+			log_parser_error(&error, &src_code);
+			(Outcome::Failed, "Invalid workflow".to_owned())
+		})?;
+
+		let ret = execute(
+			workflow,
+			job.variables.clone(),
+			self.router.client(),
+			RunOptions {
+				seed: job.seed,
+				..self.options.clone()
+			},
+			&format!("[{}] ", job.name),
+		)
+		.await;
+
+		let Err(error) = ret else {
+			return Ok(());
+		};
+		log_interpreter_error(&error, &src_code);
+
+		let message = format::format_interpreter_error(&error, &src_code, ColorMode::None).title;
+		match error.kind {
+			ErrorKind::OrderDone => Ok(()),
+			ErrorKind::OrderCancel => Err((Outcome::Canceled, message)),
+			_ => Err((Outcome::Failed, message)),
+		}
+	}
+
+	async fn skip(&self, job: &Job, scheduled: DateTime<Utc>, reason: &str) {
+		self.record(RunRecord {
+			job: job.name.clone(),
+			scheduled: timestamp(scheduled),
+			started: None,
+			finished: None,
+			outcome: Outcome::Skipped,
+			message: Some(reason.to_owned()),
+		})
+		.await;
+	}
+
+	async fn record(&self, record: RunRecord) {
+		match record.outcome {
+			Outcome::Succeeded => info!("Job `{}` succeeded", record.job),
+			outcome => warn!(
+				"Job `{}` {}: {}",
+				record.job,
+				outcome.as_str(),
+				record.message.as_deref().unwrap_or_default()
+			),
+		}
+
+		let Some(history) = &self.history else {
+			return;
+		};
+		let line = match serde_json::to_string(&record) {
+			Ok(line) => line + "\n",
+			Err(err) => {
+				error!("Failed to serialize run record: {}!", err);
+				return;
+			}
+		};
+		let file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(history)
+			.await;
+		if let Err(err) = match file {
+			Ok(mut file) => file.write_all(line.as_bytes()).await,
+			Err(err) => Err(err),
+		} {
+			error!("Failed to write run record to `{}`: {}!", history, err);
+		}
+	}
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+	time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use serde_json::Value as JsonValue;
+	use tokio::sync::watch;
+
+	use super::*;
+
+	/// Clock which only moves when the test advances it.
+	#[derive(Clone)]
+	struct FakeClock {
+		now: Arc<watch::Sender<DateTime<Utc>>>,
+		sleeping: Arc<watch::Sender<Vec<DateTime<Utc>>>>,
+	}
+
+	impl FakeClock {
+		fn new(now: &str) -> Self {
+			Self {
+				now: Arc::new(watch::Sender::new(time(now))),
+				sleeping: Arc::new(watch::Sender::new(Vec::new())),
+			}
+		}
+
+		/// Waits until the scheduler sleeps until `time`, so all earlier runs are started.
+		async fn sleeping(&self, time: &str) {
+			let time = self::time(time);
+			let mut sleeping = self.sleeping.subscribe();
+			tokio::time::timeout(
+				Duration::from_secs(5),
+				sleeping.wait_for(|sleeping| sleeping.contains(&time)),
+			)
+			.await
+			.expect("scheduler does not wait for the time")
+			.unwrap();
+		}
+
+		async fn advance(&self, time: &str) {
+			self.sleeping(time).await;
+			self.now.send_replace(self::time(time));
+		}
+	}
+
+	impl Clock for FakeClock {
+		fn now(&self) -> DateTime<Utc> {
+			*self.now.borrow()
+		}
+
+		async fn sleep_until(&self, time: DateTime<Utc>) {
+			let mut now = self.now.subscribe();
+			self.sleeping.send_modify(|sleeping| sleeping.push(time));
+			now.wait_for(|now| *now >= time).await.ok();
+		}
+	}
+
+	fn time(time: &str) -> DateTime<Utc> {
+		time.parse().unwrap()
+	}
+
+	/// Schedules the workflow every minute and returns the history file.
+	fn start(name: &str, overlap: &str, actions: &str, clock: FakeClock) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("scheduler-{}-{}", name, std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("job.wdl"), format!("actions {{\n{}\n}}", actions)).unwrap();
+		let schedule = serde_json::json!({
+			"jobs": [{ "name": name, "cron": "* * * * *", "file": "job.wdl", "overlap": overlap }]
+		});
+		std::fs::write(dir.join("schedule.json"), schedule.to_string()).unwrap();
+		let history = dir.join("history.jsonl");
+		std::fs::remove_file(&history).ok();
+
+		let file = dir.join("schedule.json").display().to_string();
+		let history_file = history.display().to_string();
+		tokio::spawn(async move {
+			scheduler(
+				&file,
+				RouterConfig::Http("http://127.0.0.1:1".to_owned()),
+				RunOptions::default(),
+				Some(history_file),
+				clock,
+			)
+			.await
+			.unwrap();
+		});
+
+		history
+	}
+
+	/// Delivers the event until `count` runs received it.
+	async fn release(event: &str, count: usize) {
+		let waiting = async {
+			let mut delivered = 0;
+			while delivered < count {
+				delivered += interpreter::send_event(event, Value::Null).await;
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		};
+		tokio::time::timeout(Duration::from_secs(5), waiting)
+			.await
+			.expect("runs do not wait for the event");
+	}
+
+	/// Returns `(scheduled, started, outcome)` of the records, sorted by the scheduled time.
+	async fn records(history: &Path, count: usize) -> Vec<(String, String, String)> {
+		let read = async {
+			loop {
+				let content = tokio::fs::read_to_string(history).await.unwrap_or_default();
+				if content.lines().count() >= count {
+					return content;
+				}
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		};
+		let content = tokio::time::timeout(Duration::from_secs(5), read)
+			.await
+			.expect("records are not written");
+
+		let mut records: Vec<_> = content
+			.lines()
+			.map(|line| {
+				let record: JsonValue = serde_json::from_str(line).unwrap();
+				let field = |name: &str| record[name].as_str().unwrap_or("-").to_owned();
+				(field("scheduled"), field("started"), field("outcome"))
+			})
+			.collect();
+		records.sort();
+
+		records
+	}
+
+	fn expected(records: &[(&str, &str, &str)]) -> Vec<(String, String, String)> {
+		records
+			.iter()
+			.map(|(scheduled, started, outcome)| {
+				(
+					scheduled.to_string(),
+					started.to_string(),
+					outcome.to_string(),
+				)
+			})
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn skips_run_while_previous_is_running() {
+		let clock = FakeClock::new("2025-01-01T10:00:30Z");
+		let history = start(
+			"skip",
+			"skip",
+			"event::wait(\"skip\"); order::cancel();",
+			clock.clone(),
+		);
+
+		clock.advance("2025-01-01T10:01:00Z").await;
+		clock.advance("2025-01-01T10:02:00Z").await;
+		clock.sleeping("2025-01-01T10:03:00Z").await;
+		release("skip", 1).await;
+
+		assert_eq!(
+			records(&history, 2).await,
+			expected(&[
+				("2025-01-01T10:01:00Z", "2025-01-01T10:01:00Z", "canceled"),
+				("2025-01-01T10:02:00Z", "-", "skipped"),
+			])
+		);
+	}
+
+	#[tokio::test]
+	async fn queues_one_run_while_previous_is_running() {
+		let clock = FakeClock::new("2025-01-01T10:00:30Z");
+		let history = start("queue", "queue", "event::wait(\"queue\");", clock.clone());
+
+		clock.advance("2025-01-01T10:01:00Z").await;
+		clock.advance("2025-01-01T10:02:00Z").await;
+		clock.advance("2025-01-01T10:03:00Z").await;
+		clock.sleeping("2025-01-01T10:04:00Z").await;
+		release("queue", 2).await;
+
+		// the queued run starts once the previous one finished
+		assert_eq!(
+			records(&history, 3).await,
+			expected(&[
+				("2025-01-01T10:01:00Z", "2025-01-01T10:01:00Z", "succeeded"),
+				("2025-01-01T10:02:00Z", "2025-01-01T10:03:00Z", "succeeded"),
+				("2025-01-01T10:03:00Z", "-", "skipped"),
+			])
+		);
+	}
+
+	#[tokio::test]
+	async fn allows_concurrent_runs() {
+		let clock = FakeClock::new("2025-01-01T10:00:30Z");
+		let history = start("allow", "allow", "event::wait(\"allow\");", clock.clone());
+
+		clock.advance("2025-01-01T10:01:00Z").await;
+		clock.advance("2025-01-01T10:02:00Z").await;
+		clock.advance("2025-01-01T10:03:00Z").await;
+		clock.sleeping("2025-01-01T10:04:00Z").await;
+		release("allow", 3).await;
+
+		assert_eq!(
+			records(&history, 3).await,
+			expected(&[
+				("2025-01-01T10:01:00Z", "2025-01-01T10:01:00Z", "succeeded"),
+				("2025-01-01T10:02:00Z", "2025-01-01T10:02:00Z", "succeeded"),
+				("2025-01-01T10:03:00Z", "2025-01-01T10:03:00Z", "succeeded"),
+			])
+		);
+	}
+}
@@ -0,0 +1,745 @@
+//! Headless router simulating vehicles which drive along the paths of a site map.
+
+use std::{
+	collections::{HashMap, HashSet},
+	error::Error,
+	process::ExitCode,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use tokio::{sync::mpsc, task::AbortHandle};
+use tokio_stream::wrappers::ReceiverStream;
+
+use router::{
+	ActionRequest, Coordinate, Router, RouterCapabilities, RouterHealth, Target, Vehicle,
+	VehicleSelector,
+	proto::{
+		self, CancelRequest, CancelResponse, CapabilitiesRequest, CapabilitiesResponse,
+		ExecuteRequest, HealthRequest, HealthResponse, ReleaseRequest, ReleaseResponse,
+		ReserveRequest, RouterRequest, RouterResponse, RouterStatus, RouterUpdate,
+		router_update::Update,
+	},
+};
+use site::Site;
+
+use crate::router::{UpdateStream, now_ms, reserve_vehicle, serve};
+
+/// Time needed to pick up or drop a load after arriving.
+const HANDLING: Duration = Duration::from_millis(500);
+
+/// Speeds must be positive and finite, otherwise no travel time can be computed.
+pub fn parse_speed(speed: &str) -> Result<f64, String> {
+	let speed: f64 = speed
+		.parse()
+		.map_err(|_| format!("`{}` is not a number", speed))?;
+	if !speed.is_finite() || speed <= 0.0 {
+		return Err(format!("expected a positive speed, but `{}` given", speed));
+	}
+
+	Ok(speed)
+}
+
+pub async fn simulator(
+	site: Site,
+	speed: f64,
+	progress: bool,
+	fleet: Vec<Vehicle>,
+	http: Option<String>,
+	quiet: bool,
+) -> Result<ExitCode, Box<dyn Error>> {
+	if site.stations.is_empty() {
+		eprintln!("The site has no stations to place the vehicles on");
+		return Ok(ExitCode::FAILURE);
+	}
+
+	serve(Simulator::new(site, speed, progress, fleet, quiet), http).await
+}
+
+pub struct Simulator {
+	site: Arc<Site>,
+	/// Speed of all vehicles in units of the site map per second.
+	speed: f64,
+	/// Report progress updates after each path segment.
+	progress: bool,
+	fleet: Vec<Vehicle>,
+	/// Run headless without printing the actions.
+	quiet: bool,
+	state: Arc<Mutex<State>>,
+}
+
+struct State {
+	/// Simulation state of the vehicles, in the same order as the fleet.
+	vehicles: Vec<SimVehicle>,
+	reserved: HashSet<String>,
+	tasks: HashMap<u64, AbortHandle>,
+}
+
+struct SimVehicle {
+	position: Coordinate,
+	/// Station the vehicle is located at, `None` if it stands on a coordinate.
+	station: Option<String>,
+	/// Station the vehicle is heading to.
+	destination: Option<String>,
+	action_id: Option<u64>,
+}
+
+/// Point along a route and the station located there.
+type Waypoint = (Coordinate, Option<String>);
+
+/// Route of a vehicle chosen to execute an action.
+struct Plan {
+	vehicle: usize,
+	/// Points along the route, excluding the current position of the vehicle.
+	waypoints: Vec<Waypoint>,
+	station: Option<String>,
+	coordinate: Option<Coordinate>,
+}
+
+/// Reasons why an action cannot be planned.
+enum Rejection {
+	Status(RouterStatus),
+	InvalidTarget(String),
+	Unavailable(String),
+}
+
+/// Prints what the simulated vehicles do, unless the simulator runs quietly.
+fn report(quiet: bool, msg: String) {
+	if !quiet {
+		eprintln!("{}", msg);
+		eprintln!();
+	}
+}
+
+impl Simulator {
+	/// The vehicles are distributed over the stations in the order of the site map.
+	fn new(site: Site, speed: f64, progress: bool, fleet: Vec<Vehicle>, quiet: bool) -> Self {
+		let mut starts = Vec::new();
+		let vehicles = fleet
+			.iter()
+			.zip(site.stations.iter().cycle())
+			.map(|(vehicle, station)| {
+				starts.push(format!(
+					"Vehicle `{}` starts at `{}`",
+					vehicle.id, station.name
+				));
+				SimVehicle {
+					position: Coordinate {
+						x: station.x,
+						y: station.y,
+					},
+					station: Some(station.name.clone()),
+					destination: None,
+					action_id: None,
+				}
+			})
+			.collect();
+		report(quiet, starts.join("\n"));
+
+		Self {
+			site: Arc::new(site),
+			speed,
+			progress,
+			fleet,
+			quiet,
+			state: Arc::new(Mutex::new(State {
+				vehicles,
+				reserved: HashSet::new(),
+				tasks: HashMap::new(),
+			})),
+		}
+	}
+
+	/// Resolves the target to the stations which may be approached, ignoring occupancy.
+	fn candidates(&self, target: &Target) -> Result<Vec<String>, Rejection> {
+		let mut stations = Vec::new();
+		for name in target.stations.iter().flatten() {
+			if self.site.station(name).is_none() {
+				return Err(Rejection::InvalidTarget(format!(
+					"Station `{}` is unknown",
+					name
+				)));
+			}
+			stations.push(name.clone());
+		}
+		for name in target.stationareas.iter().flatten() {
+			let Some(area) = self.site.stationarea(name) else {
+				return Err(Rejection::InvalidTarget(format!(
+					"Station area `{}` is unknown",
+					name
+				)));
+			};
+			stations.extend(area.stations.iter().cloned());
+		}
+
+		if let Some(not) = &target.not {
+			let mut excluded: Vec<&String> = not.stations.iter().flatten().collect();
+			for name in not.stationareas.iter().flatten() {
+				if let Some(area) = self.site.stationarea(name) {
+					excluded.extend(area.stations.iter());
+				}
+			}
+			stations.retain(|station| !excluded.contains(&station));
+		}
+
+		Ok(stations)
+	}
+
+	/// Chooses the vehicle and station with the shortest route.
+	fn plan(&self, state: &State, action: &ActionRequest) -> Result<Plan, Rejection> {
+		let vehicles: Vec<usize> = match &action.vehicle {
+			Some(id) => {
+				let Some(index) = self.fleet.iter().position(|vehicle| &vehicle.id == id) else {
+					return Err(Rejection::Unavailable(format!(
+						"Vehicle `{}` is unknown",
+						id
+					)));
+				};
+				vec![index]
+			}
+			None => (0..self.fleet.len())
+				.filter(|index| !state.reserved.contains(&self.fleet[*index].id))
+				.collect(),
+		};
+		let vehicles: Vec<usize> = vehicles
+			.into_iter()
+			.filter(|index| state.vehicles[*index].action_id.is_none())
+			.collect();
+		if vehicles.is_empty() {
+			return Err(Rejection::Unavailable(
+				"No idle vehicle available".to_owned(),
+			));
+		}
+
+		let Some(target) = &action.target else {
+			// actions without a target are executed in place
+			let vehicle = vehicles[0];
+			let sim_vehicle = &state.vehicles[vehicle];
+			return Ok(Plan {
+				vehicle,
+				waypoints: Vec::new(),
+				station: sim_vehicle.station.clone(),
+				coordinate: None,
+			});
+		};
+
+		if let Some(coordinates) = &target.coordinates {
+			let excluded = target
+				.not
+				.as_ref()
+				.and_then(|not| not.coordinates.clone())
+				.unwrap_or_default();
+			let Some(coordinate) = coordinates
+				.iter()
+				.find(|coordinate| !excluded.contains(coordinate))
+			else {
+				return Err(Rejection::Status(RouterStatus::NoStationLeft));
+			};
+
+			// coordinates are approached in a straight line
+			return Ok(Plan {
+				vehicle: vehicles[0],
+				waypoints: vec![(coordinate.clone(), None)],
+				station: None,
+				coordinate: Some(coordinate.clone()),
+			});
+		}
+
+		// stations are occupied by vehicles standing on them or heading to them
+		let mut occupants: HashMap<&String, usize> = HashMap::new();
+		for (index, vehicle) in state.vehicles.iter().enumerate() {
+			for station in [&vehicle.station, &vehicle.destination]
+				.into_iter()
+				.flatten()
+			{
+				occupants.insert(station, index);
+			}
+		}
+		let candidates = self.candidates(target)?;
+		let pairs: Vec<(usize, &String)> = vehicles
+			.iter()
+			.flat_map(|vehicle| candidates.iter().map(move |station| (*vehicle, station)))
+			.filter(|(vehicle, station)| {
+				occupants
+					.get(station)
+					.is_none_or(|occupant| occupant == vehicle)
+			})
+			.collect();
+		if pairs.is_empty() {
+			return Err(Rejection::Status(RouterStatus::NoStationLeft));
+		}
+
+		let mut best: Option<(f64, Plan)> = None;
+		for (vehicle, station) in pairs {
+			let Some((distance, waypoints)) = self.route(&state.vehicles[vehicle], station) else {
+				continue;
+			};
+			if best.as_ref().is_none_or(|(best, _)| distance < *best) {
+				best = Some((
+					distance,
+					Plan {
+						vehicle,
+						waypoints,
+						station: Some(station.clone()),
+						coordinate: None,
+					},
+				));
+			}
+		}
+
+		best.map(|(_, plan)| plan)
+			.ok_or(Rejection::Status(RouterStatus::PathBlocked))
+	}
+
+	/// Route from the vehicle to the station, vehicles off the stations first drive
+	/// straight to the closest station.
+	fn route(&self, vehicle: &SimVehicle, station: &str) -> Option<(f64, Vec<Waypoint>)> {
+		let start = match &vehicle.station {
+			Some(start) => self.site.station(start)?,
+			None => self
+				.site
+				.nearest(vehicle.position.x, vehicle.position.y, None)?,
+		};
+
+		let mut position = vehicle.position.clone();
+		let mut distance = 0.0;
+		let mut waypoints = Vec::new();
+		for station in self.site.route(&start.name, station)? {
+			let point = Coordinate {
+				x: station.x,
+				y: station.y,
+			};
+			let leg = (point.x - position.x).hypot(point.y - position.y);
+			if leg > 0.0 {
+				distance += leg;
+				waypoints.push((point.clone(), Some(station.name.clone())));
+			}
+			position = point;
+		}
+
+		Some((distance, waypoints))
+	}
+
+	/// Plans the action and drives the chosen vehicle in the background,
+	/// progress updates and the final response are sent to the returned receiver.
+	async fn start(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+	) -> Result<mpsc::Receiver<Result<RouterUpdate, tonic::Status>>, tonic::Status> {
+		let (tx, rx) = mpsc::channel(16);
+		let started_at_ms = now_ms();
+
+		let mut state = self.state.lock().unwrap();
+		let plan = match self.plan(&state, &action) {
+			Ok(plan) => plan,
+			Err(Rejection::Status(status)) => {
+				report(
+					self.quiet,
+					format!(
+						"Action `{}` ({}) failed with `{:?}`",
+						action_id, action.name, status
+					),
+				);
+				let response = RouterResponse {
+					status: status as i32,
+					station: None,
+					coordinate: None,
+					vehicle: action.vehicle,
+					started_at_ms: Some(started_at_ms),
+					finished_at_ms: Some(now_ms()),
+				};
+				tx.try_send(Ok(RouterUpdate {
+					update: Some(Update::Response(response)),
+				}))
+				.ok();
+				return Ok(rx);
+			}
+			Err(Rejection::InvalidTarget(msg)) => return Err(tonic::Status::invalid_argument(msg)),
+			Err(Rejection::Unavailable(msg)) => {
+				return Err(tonic::Status::resource_exhausted(msg));
+			}
+		};
+
+		let vehicle_id = self.fleet[plan.vehicle].id.clone();
+		report(
+			self.quiet,
+			format!(
+				"Action `{}` ({}) by vehicle `{}` to `{}`",
+				action_id,
+				action.name,
+				vehicle_id,
+				plan.station
+					.clone()
+					.or(plan
+						.coordinate
+						.as_ref()
+						.map(|coordinate| format!("({}, {})", coordinate.x, coordinate.y)))
+					.unwrap_or_else(|| "current position".to_owned())
+			),
+		);
+
+		let sim_vehicle = &mut state.vehicles[plan.vehicle];
+		sim_vehicle.action_id = Some(action_id);
+		sim_vehicle.destination = plan.station.clone();
+
+		let site_state = self.state.clone();
+		let speed = self.speed;
+		let progress = self.progress;
+		let quiet = self.quiet;
+		let handling = matches!(action.name.as_str(), "pickup" | "drop");
+		let task = tokio::spawn(async move {
+			let start = site_state.lock().unwrap().vehicles[plan.vehicle]
+				.position
+				.clone();
+			let mut legs = Vec::new();
+			let mut position = start;
+			for (point, _) in &plan.waypoints {
+				legs.push((point.x - position.x).hypot(point.y - position.y) / speed);
+				position = point.clone();
+			}
+			let total: f64 = legs.iter().sum();
+
+			let mut elapsed = 0.0;
+			for ((point, station), leg) in plan.waypoints.iter().zip(legs) {
+				tokio::time::sleep(Duration::from_secs_f64(leg)).await;
+				elapsed += leg;
+				{
+					let mut state = site_state.lock().unwrap();
+					let vehicle = &mut state.vehicles[plan.vehicle];
+					vehicle.position = point.clone();
+					vehicle.station = station.clone();
+				}
+
+				if progress {
+					let update = RouterUpdate {
+						update: Some(Update::Progress(proto::RouterProgress {
+							vehicle: Some(vehicle_id.clone()),
+							eta_ms: Some((total - elapsed) * 1000.0),
+							position: Some(point.clone().into()),
+							percent: Some(elapsed / total * 100.0),
+						})),
+					};
+					tx.send(Ok(update)).await.ok();
+				}
+			}
+			if handling {
+				tokio::time::sleep(HANDLING).await;
+			}
+
+			{
+				let mut state = site_state.lock().unwrap();
+				let vehicle = &mut state.vehicles[plan.vehicle];
+				vehicle.destination = None;
+				vehicle.action_id = None;
+				state.tasks.remove(&action_id);
+			}
+			report(quiet, format!("Action `{}` done", action_id));
+
+			let response = RouterResponse {
+				status: RouterStatus::Done as i32,
+				station: plan.station,
+				coordinate: plan.coordinate.map(Coordinate::into),
+				vehicle: Some(vehicle_id),
+				started_at_ms: Some(started_at_ms),
+				finished_at_ms: Some(now_ms()),
+			};
+			tx.send(Ok(RouterUpdate {
+				update: Some(Update::Response(response)),
+			}))
+			.await
+			.ok();
+		});
+		state.tasks.insert(action_id, task.abort_handle());
+
+		Ok(rx)
+	}
+
+	/// Runs the action and waits for the final response.
+	async fn run(
+		&self,
+		request: tonic::Request<RouterRequest>,
+		name: &str,
+	) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
+		let request = request.into_inner();
+		let mut action = ActionRequest::new(name, request.target.map(Target::from));
+		action.vehicle = request.vehicle;
+
+		let mut rx = self.start(request.action_id, action).await?;
+		while let Some(update) = rx.recv().await {
+			if let Some(Update::Response(response)) = update?.update {
+				return Ok(tonic::Response::new(response));
+			}
+		}
+
+		Err(tonic::Status::aborted("Action canceled"))
+	}
+
+	async fn run_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+		name: &str,
+	) -> Result<tonic::Response<UpdateStream>, tonic::Status> {
+		let request = request.into_inner();
+		let mut action = ActionRequest::new(name, request.target.map(Target::from));
+		action.vehicle = request.vehicle;
+
+		let rx = self.start(request.action_id, action).await?;
+		Ok(tonic::Response::new(ReceiverStream::new(rx)))
+	}
+}
+
+#[tonic::async_trait]
+impl Router for Simulator {
+	async fn pickup(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
+		self.run(request, "pickup").await
+	}
+
+	async fn drop(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
+		self.run(request, "drop").await
+	}
+
+	async fn drive(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<RouterResponse>, tonic::Status> {
+		self.run(request, "drive").await
+	}
+
+	type PickupStreamStream = UpdateStream;
+
+	async fn pickup_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::PickupStreamStream>, tonic::Status> {
+		self.run_stream(request, "pickup").await
+	}
+
+	type DropStreamStream = UpdateStream;
+
+	async fn drop_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::DropStreamStream>, tonic::Status> {
+		self.run_stream(request, "drop").await
+	}
+
+	type DriveStreamStream = UpdateStream;
+
+	async fn drive_stream(
+		&self,
+		request: tonic::Request<RouterRequest>,
+	) -> Result<tonic::Response<Self::DriveStreamStream>, tonic::Status> {
+		self.run_stream(request, "drive").await
+	}
+
+	type ExecuteStream = UpdateStream;
+
+	async fn execute(
+		&self,
+		request: tonic::Request<ExecuteRequest>,
+	) -> Result<tonic::Response<Self::ExecuteStream>, tonic::Status> {
+		let action_id = request.get_ref().action_id;
+		let action = ActionRequest::try_from(request.into_inner())
+			.map_err(|err| tonic::Status::invalid_argument(err.to_string()))?;
+
+		let rx = self.start(action_id, action).await?;
+		Ok(tonic::Response::new(ReceiverStream::new(rx)))
+	}
+
+	async fn cancel(
+		&self,
+		request: tonic::Request<CancelRequest>,
+	) -> Result<tonic::Response<CancelResponse>, tonic::Status> {
+		let action_id = request.into_inner().action_id;
+
+		// the vehicle stops at the last station it reached
+		let mut state = self.state.lock().unwrap();
+		if let Some(task) = state.tasks.remove(&action_id) {
+			task.abort();
+			for vehicle in &mut state.vehicles {
+				if vehicle.action_id == Some(action_id) {
+					vehicle.action_id = None;
+					vehicle.destination = None;
+				}
+			}
+			report(self.quiet, format!("Action `{}` canceled", action_id));
+		}
+
+		Ok(tonic::Response::new(CancelResponse {}))
+	}
+
+	async fn reserve(
+		&self,
+		request: tonic::Request<ReserveRequest>,
+	) -> Result<tonic::Response<proto::Vehicle>, tonic::Status> {
+		let selector = VehicleSelector::from(request.into_inner().selector.unwrap_or_default());
+
+		let mut state = self.state.lock().unwrap();
+		let Some(vehicle) = reserve_vehicle(&self.fleet, &mut state.reserved, &selector) else {
+			return Err(tonic::Status::resource_exhausted(
+				"No matching vehicle available",
+			));
+		};
+
+		Ok(tonic::Response::new(vehicle.into()))
+	}
+
+	async fn release(
+		&self,
+		request: tonic::Request<ReleaseRequest>,
+	) -> Result<tonic::Response<ReleaseResponse>, tonic::Status> {
+		let vehicle = request.into_inner().vehicle;
+		self.state.lock().unwrap().reserved.remove(&vehicle);
+
+		report(self.quiet, format!("Release vehicle `{}`", vehicle));
+
+		Ok(tonic::Response::new(ReleaseResponse {}))
+	}
+	async fn health(
+		&self,
+		_request: tonic::Request<HealthRequest>,
+	) -> Result<tonic::Response<HealthResponse>, tonic::Status> {
+		Ok(tonic::Response::new(RouterHealth::ready().into()))
+	}
+
+	async fn capabilities(
+		&self,
+		_request: tonic::Request<CapabilitiesRequest>,
+	) -> Result<tonic::Response<CapabilitiesResponse>, tonic::Status> {
+		let capabilities = RouterCapabilities {
+			actions: None,
+			statuses: vec![
+				router::RouterStatus::Done,
+				router::RouterStatus::PathBlocked,
+				router::RouterStatus::NoStationLeft,
+			],
+			progress: self.progress,
+			reservation: true,
+		};
+
+		Ok(tonic::Response::new(capabilities.into()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse_vehicle;
+
+	/// Stations `a` and `b` form the hall, `c` and `d` the yard.
+	const SITE: &str = r#"{
+		"stations": [
+			{ "name": "a", "x": 0, "y": 0 },
+			{ "name": "b", "x": 10, "y": 0 },
+			{ "name": "c", "x": 20, "y": 0 },
+			{ "name": "d", "x": 0, "y": 10 }
+		],
+		"stationareas": [
+			{ "name": "hall", "stations": ["a", "b"] },
+			{ "name": "yard", "stations": ["c", "d"] }
+		],
+		"paths": [
+			{ "from": "a", "to": "b" },
+			{ "from": "b", "to": "c" },
+			{ "from": "a", "to": "d" }
+		]
+	}"#;
+
+	/// The vehicles start on the stations in the order of the site map.
+	fn simulator(vehicles: &[&str]) -> Simulator {
+		let fleet = vehicles
+			.iter()
+			.map(|vehicle| parse_vehicle(vehicle))
+			.collect();
+
+		Simulator::new(Site::from_json(SITE).unwrap(), 1.0, false, fleet, true)
+	}
+
+	fn target(target: serde_json::Value) -> Target {
+		serde_json::from_value(target).unwrap()
+	}
+
+	fn candidates(target: serde_json::Value) -> Vec<String> {
+		let Ok(candidates) = simulator(&["agv1"]).candidates(&self::target(target)) else {
+			panic!("target rejected");
+		};
+
+		candidates
+	}
+
+	#[test]
+	fn resolves_station_areas() {
+		assert_eq!(
+			candidates(serde_json::json!({ "stationareas": ["hall"] })),
+			["a", "b"]
+		);
+		assert_eq!(
+			candidates(serde_json::json!({ "stations": ["c"], "stationareas": ["hall"] })),
+			["c", "a", "b"]
+		);
+		assert!(matches!(
+			simulator(&["agv1"])
+				.candidates(&target(serde_json::json!({ "stationareas": ["hall2"] }))),
+			Err(Rejection::InvalidTarget(_))
+		));
+	}
+
+	#[test]
+	fn excludes_not() {
+		assert_eq!(
+			candidates(serde_json::json!({
+				"stationareas": ["hall", "yard"],
+				"not": { "stations": ["a"], "stationareas": ["yard"] }
+			})),
+			["b"]
+		);
+		assert!(
+			candidates(
+				serde_json::json!({ "stations": ["a"], "not": { "stationareas": ["hall"] } })
+			)
+			.is_empty()
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_speed() {
+		assert_eq!(parse_speed("2.5"), Ok(2.5));
+		for speed in ["0", "-1", "inf", "NaN", "fast"] {
+			assert!(parse_speed(speed).is_err(), "{}", speed);
+		}
+	}
+
+	#[tokio::test]
+	async fn no_station_left_if_all_are_occupied() {
+		let simulator = simulator(&["agv1", "agv2"]);
+
+		// `agv1` stands on `a` and heads to `c`, so both are occupied
+		let mut drive = ActionRequest::new(
+			"drive",
+			Some(target(serde_json::json!({ "stations": ["c"] }))),
+		);
+		drive.vehicle = Some("agv1".to_owned());
+		simulator.start(1, drive).await.unwrap();
+
+		let pickup = ActionRequest::new(
+			"pickup",
+			Some(target(serde_json::json!({ "stations": ["a", "c"] }))),
+		);
+		let update = simulator.start(2, pickup).await.unwrap().recv().await;
+
+		let Some(Ok(RouterUpdate {
+			update: Some(Update::Response(response)),
+		})) = update
+		else {
+			panic!("unexpected update {:?}", update);
+		};
+		assert_eq!(response.status, RouterStatus::NoStationLeft as i32);
+	}
+}
@@ -0,0 +1,280 @@
+//! Simulation of a single vehicle speaking VDA 5050 over MQTT.
+
+use std::{error::Error, process::ExitCode, time::Duration};
+
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+
+use router::{
+	Vda5050Config,
+	vda5050::{self, ActionState, EdgeState, Header, NodeState},
+};
+
+/// Time the vehicle needs to travel along a single edge.
+const STEP: Duration = Duration::from_secs(1);
+
+/// Splits `<host>:<port>` into its parts.
+pub fn parse_broker(broker: &str) -> Option<(String, u16)> {
+	let (host, port) = broker.rsplit_once(':')?;
+
+	Some((host.to_owned(), port.parse().ok()?))
+}
+
+pub async fn vehicle(
+	config: Vda5050Config,
+	fail_actions: bool,
+) -> Result<ExitCode, Box<dyn Error>> {
+	let mut options = MqttOptions::new(
+		format!("vehicle-{}", config.serial_number),
+		config.host.clone(),
+		config.port,
+	);
+	options.set_keep_alive(Duration::from_secs(5));
+	let (client, mut event_loop) = AsyncClient::new(options, 10);
+
+	let mut vehicle = Vehicle {
+		config,
+		client,
+		header_id: 0,
+		fail_actions,
+		order: None,
+		state: vda5050::State {
+			last_node_id: "home".to_owned(),
+			agv_position: Some(vda5050::AgvPosition {
+				position_initialized: true,
+				..Default::default()
+			}),
+			battery_state: vda5050::BatteryState {
+				battery_charge: 100.0,
+				charging: false,
+			},
+			..Default::default()
+		},
+	};
+
+	let mut interval = tokio::time::interval(STEP);
+	loop {
+		tokio::select! {
+			event = event_loop.poll() => match event {
+				Ok(Event::Incoming(Packet::ConnAck(_))) => vehicle.connected().await?,
+				Ok(Event::Incoming(Packet::Publish(publish))) => {
+					vehicle.receive(&publish.topic, &publish.payload).await?;
+				}
+				Ok(_) => {}
+				Err(err) => {
+					eprintln!("Connection to broker failed: {}", err);
+					tokio::time::sleep(STEP).await;
+				}
+			},
+			_ = interval.tick() => vehicle.step().await?,
+		}
+	}
+}
+
+struct Vehicle {
+	config: Vda5050Config,
+	client: AsyncClient,
+	header_id: u32,
+	/// Report all actions as failed.
+	fail_actions: bool,
+	order: Option<vda5050::Order>,
+	state: vda5050::State,
+}
+
+impl Vehicle {
+	async fn connected(&mut self) -> Result<(), Box<dyn Error>> {
+		for topic in ["order", "instantActions"] {
+			self.client
+				.subscribe(self.config.topic(topic), QoS::AtLeastOnce)
+				.await?;
+		}
+		eprintln!(
+			"Vehicle `{}` connected, waiting for orders",
+			self.config.serial_number
+		);
+		eprintln!();
+
+		self.publish_state().await
+	}
+
+	async fn receive(&mut self, topic: &str, payload: &[u8]) -> Result<(), Box<dyn Error>> {
+		if topic == self.config.topic("order") {
+			match serde_json::from_slice(payload) {
+				Ok(order) => self.accept_order(order),
+				Err(err) => eprintln!("Invalid order received: {}", err),
+			}
+		} else if topic == self.config.topic("instantActions") {
+			match serde_json::from_slice::<vda5050::InstantActions>(payload) {
+				Ok(instant_actions) => {
+					for action in instant_actions.actions {
+						self.instant_action(action);
+					}
+				}
+				Err(err) => eprintln!("Invalid instant actions received: {}", err),
+			}
+		} else {
+			return Ok(());
+		}
+
+		self.publish_state().await
+	}
+
+	fn accept_order(&mut self, order: vda5050::Order) {
+		eprintln!(
+			"Order `{}` to node `{}`",
+			order.order_id,
+			order
+				.nodes
+				.last()
+				.map(|node| node.node_id.as_str())
+				.unwrap_or_default()
+		);
+
+		self.state.order_id = order.order_id.clone();
+		self.state.order_update_id = order.order_update_id;
+		// the vehicle is already located on the first node,
+		// orders with a single node only execute its actions
+		let on_first_node = order.nodes.len() > 1
+			&& order
+				.nodes
+				.first()
+				.is_some_and(|node| node.node_id == self.state.last_node_id);
+		self.state.node_states = order
+			.nodes
+			.iter()
+			.skip(usize::from(on_first_node))
+			.map(|node| NodeState {
+				node_id: node.node_id.clone(),
+				sequence_id: node.sequence_id,
+				released: node.released,
+			})
+			.collect();
+		self.state.edge_states = order
+			.edges
+			.iter()
+			.map(|edge| EdgeState {
+				edge_id: edge.edge_id.clone(),
+				sequence_id: edge.sequence_id,
+				released: edge.released,
+			})
+			.collect();
+		self.state.action_states = order
+			.nodes
+			.iter()
+			.flat_map(|node| node.actions.iter())
+			.map(|action| ActionState {
+				action_id: action.action_id.clone(),
+				action_type: action.action_type.clone(),
+				action_status: "WAITING".to_owned(),
+				result_description: None,
+			})
+			.collect();
+		self.state.driving = !self.state.node_states.is_empty();
+
+		self.order = Some(order);
+	}
+
+	fn instant_action(&mut self, action: vda5050::Action) {
+		let mut status = "FINISHED";
+		match action.action_type.as_str() {
+			"cancelOrder" => {
+				eprintln!("Cancel order `{}`", self.state.order_id);
+				self.state.node_states.clear();
+				self.state.edge_states.clear();
+				self.state.driving = false;
+				for action in &mut self.state.action_states {
+					if action.action_status != "FINISHED" {
+						action.action_status = "FAILED".to_owned();
+					}
+				}
+				self.order = None;
+			}
+			action_type => {
+				eprintln!("Instant action `{}` not supported", action_type);
+				status = "FAILED";
+			}
+		}
+		eprintln!();
+
+		self.state.action_states.push(ActionState {
+			action_id: action.action_id,
+			action_type: action.action_type,
+			action_status: status.to_owned(),
+			result_description: None,
+		});
+	}
+
+	/// Moves the vehicle to the next node of the current order
+	/// and executes the actions located there.
+	/// The state is published on every step, also while the vehicle is idle.
+	async fn step(&mut self) -> Result<(), Box<dyn Error>> {
+		if self.state.node_states.is_empty() {
+			return self.publish_state().await;
+		}
+
+		let node_state = self.state.node_states.remove(0);
+		if !self.state.edge_states.is_empty() {
+			self.state.edge_states.remove(0);
+		}
+		self.state.last_node_id = node_state.node_id.clone();
+		self.state.last_node_sequence_id = node_state.sequence_id;
+		self.state.driving = !self.state.node_states.is_empty();
+
+		let node = self.order.as_ref().and_then(|order| {
+			order
+				.nodes
+				.iter()
+				.find(|node| node.sequence_id == node_state.sequence_id)
+		});
+		let node_position = node.and_then(|node| node.node_position.as_ref());
+		if let (Some(node_position), Some(position)) = (node_position, &mut self.state.agv_position)
+		{
+			position.x = node_position.x;
+			position.y = node_position.y;
+		}
+
+		let actions: Vec<_> = node
+			.map(|node| node.actions.iter().map(|a| a.action_id.clone()).collect())
+			.unwrap_or_default();
+		let status = if self.fail_actions {
+			"FAILED"
+		} else {
+			"FINISHED"
+		};
+		for action in &mut self.state.action_states {
+			if actions.contains(&action.action_id) {
+				eprintln!("{} `{}`", status, action.action_type);
+				action.action_status = status.to_owned();
+			}
+		}
+
+		eprintln!("Reached node `{}`", node_state.node_id);
+		if self.state.node_states.is_empty() {
+			eprintln!("Order `{}` done", self.state.order_id);
+			eprintln!();
+		}
+
+		self.publish_state().await
+	}
+
+	async fn publish_state(&mut self) -> Result<(), Box<dyn Error>> {
+		self.state.header = Header {
+			header_id: self.header_id,
+			timestamp: vda5050::timestamp(),
+			version: vda5050::VERSION.to_owned(),
+			manufacturer: self.config.manufacturer.clone(),
+			serial_number: self.config.serial_number.clone(),
+		};
+		self.header_id += 1;
+
+		self.client
+			.publish(
+				self.config.topic("state"),
+				QoS::AtLeastOnce,
+				false,
+				serde_json::to_vec(&self.state)?,
+			)
+			.await?;
+
+		Ok(())
+	}
+}
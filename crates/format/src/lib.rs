@@ -150,6 +150,9 @@ pub fn format_interpreter_error(
 	mode: ColorMode,
 ) -> Error {
 	let title = match &error.kind {
+		interpreter::ErrorKind::ActionFailed { status } => {
+			format!("Action failed, router reported `{:?}`!", status)
+		}
 		interpreter::ErrorKind::ArityMismatch { expected, given } => {
 			format!(
 				"Invalid count of function call parameter, expected `{}`, given `{}`!",
@@ -161,11 +164,17 @@ pub fn format_interpreter_error(
 		interpreter::ErrorKind::InvalidType { msg } => {
 			format!("Invalid types, {}!", msg)
 		}
+		interpreter::ErrorKind::InvalidTarget(err) => {
+			format!("Invalid target, {}!", err)
+		}
 		interpreter::ErrorKind::MissingArgument { id } => {
 			format!("Argument `{}` missing!", id)
 		}
 		interpreter::ErrorKind::OrderCancel => "Order canceled!".to_owned(),
 		interpreter::ErrorKind::OrderDone => "Order done!".to_owned(),
+		interpreter::ErrorKind::Router(err) => {
+			format!("Router error, {}!", err)
+		}
 		interpreter::ErrorKind::UnknownArgument { id } => {
 			format!("Named argument `{}` unknown!", id)
 		}
@@ -31,6 +31,12 @@ impl Channel {
 		self.rx.lock().await.recv().await
 	}
 
+	/// The sender does not keep the channel alive,
+	/// sending fails once the channel is closed or dropped.
+	pub fn sender(&self) -> Sender<Value> {
+		self.tx.clone()
+	}
+
 	pub async fn close(&self) {
 		self.rx.lock().await.close();
 	}
@@ -1,54 +1,78 @@
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	sync::{
 		Arc,
 		atomic::{AtomicU32, Ordering},
 	},
 };
 
-use log::error;
+use log::{error, info};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use tokio::{
 	sync::{Mutex, RwLock, mpsc::Sender},
 	task::JoinHandle,
 };
 
 use ast::{Identifier, Node};
+use router::RouterClient;
+use site::Site;
 
 use crate::{
-	Channel, ChannelId, Error, ErrorKind, FunctionId, FunctionValue, LogEntry, Router, Scope,
-	Value, wdl_std::resolve_id,
+	Channel, ChannelId, Error, ErrorKind, FunctionId, FunctionValue, LockPermit, LogEntry, Router,
+	Scope, Value, wdl_std::resolve_id,
 };
 
 pub struct Environment {
 	pub global_scope: Arc<Scope>,
 	pub router: Router,
+	pub site: Option<Site>,
 	user_log_ch: Mutex<Sender<LogEntry>>,
 	error_ch: Mutex<Sender<Error>>,
 	handles: Mutex<Vec<JoinHandle<Result<(), Error>>>>,
 	functions: RwLock<HashMap<Identifier, FunctionValue>>,
 	channels: RwLock<HashMap<ChannelId, Channel>>,
 	channel_id: AtomicU32,
+	actions: std::sync::Mutex<HashSet<u64>>,
+	cancels: std::sync::Mutex<Vec<JoinHandle<()>>>,
+	vehicles: Mutex<HashSet<String>>,
+	locks: Mutex<HashMap<String, Vec<LockPermit>>>,
+	rng: std::sync::Mutex<ChaCha8Rng>,
 }
 
 impl Environment {
 	pub fn new(
 		global_scope: Arc<Scope>,
 		router: Router,
+		site: Option<Site>,
 		user_log_ch: Sender<LogEntry>,
 		error_ch: Sender<Error>,
+		seed: u64,
 	) -> Self {
 		Environment {
 			global_scope,
 			router,
+			site,
 			user_log_ch: Mutex::new(user_log_ch),
 			error_ch: Mutex::new(error_ch),
 			handles: Mutex::new(Vec::new()),
 			functions: RwLock::new(HashMap::new()),
 			channels: RwLock::new(HashMap::new()),
 			channel_id: AtomicU32::new(0),
+			actions: std::sync::Mutex::new(HashSet::new()),
+			cancels: std::sync::Mutex::new(Vec::new()),
+			vehicles: Mutex::new(HashSet::new()),
+			locks: Mutex::new(HashMap::new()),
+			rng: std::sync::Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
 		}
 	}
 
+	/// All random values of a workflow run are drawn from the same generator,
+	/// so runs with the same seed draw the same values.
+	pub fn random<T>(&self, f: impl FnOnce(&mut ChaCha8Rng) -> T) -> T {
+		f(&mut self.rng.lock().unwrap())
+	}
+
 	pub async fn push_handle(&self, handle: JoinHandle<Result<(), Error>>) {
 		self.handles.lock().await.push(handle);
 	}
@@ -57,6 +81,101 @@ impl Environment {
 		self.handles.lock().await.pop()
 	}
 
+	/// Registers a router action as in progress until the returned guard is finished.
+	pub fn start_action(self: &Arc<Self>, action_id: u64) -> ActionGuard {
+		self.actions.lock().unwrap().insert(action_id);
+
+		ActionGuard {
+			env: self.clone(),
+			action_id,
+			done: false,
+		}
+	}
+
+	/// Cancels the action at the router, unless it already finished or was canceled.
+	async fn cancel_action(&self, action_id: u64) {
+		if !self.actions.lock().unwrap().remove(&action_id) {
+			return;
+		}
+
+		info!("Cancel router action `{}`", action_id);
+		if let Err(err) = self.router.cancel(action_id).await {
+			error!("Failed to cancel router action `{}`: {}", action_id, err);
+		}
+	}
+
+	/// Aborts all background tasks and cancels all router actions
+	/// which are still in progress.
+	pub async fn cancel(&self) {
+		while let Some(handle) = self.pop_handle().await {
+			handle.abort();
+			// the task is canceled, so the join error can be ignored
+			handle.await.ok();
+		}
+
+		let actions: Vec<u64> = self.actions.lock().unwrap().iter().copied().collect();
+		for action_id in actions {
+			self.cancel_action(action_id).await;
+		}
+
+		// cancellations started by the actions of aborted tasks
+		let cancels: Vec<_> = self.cancels.lock().unwrap().drain(..).collect();
+		for handle in cancels {
+			handle.await.ok();
+		}
+	}
+
+	/// Registers a vehicle as reserved until `release_vehicle` is called.
+	pub async fn reserve_vehicle(&self, vehicle: String) {
+		self.vehicles.lock().await.insert(vehicle);
+	}
+
+	pub async fn release_vehicle(&self, vehicle: &str) {
+		self.vehicles.lock().await.remove(vehicle);
+	}
+
+	/// Releases all vehicles still reserved at the router.
+	pub async fn release_vehicles(&self) {
+		let vehicles: Vec<String> = self.vehicles.lock().await.drain().collect();
+		for vehicle in vehicles {
+			info!("Release vehicle `{}`", vehicle);
+			if let Err(err) = self.router.release(&vehicle).await {
+				error!("Failed to release vehicle `{}`: {}", vehicle, err);
+			}
+		}
+	}
+
+	/// Keeps the lock held until `release_lock` is called.
+	pub async fn hold_lock(&self, name: String, permit: LockPermit) {
+		self.locks
+			.lock()
+			.await
+			.entry(name)
+			.or_default()
+			.push(permit);
+	}
+
+	/// Returns `false` if the workflow does not hold the lock.
+	pub async fn release_lock(&self, name: &str) -> bool {
+		let mut locks = self.locks.lock().await;
+		let Some(permits) = locks.get_mut(name) else {
+			return false;
+		};
+
+		permits.pop();
+		if permits.is_empty() {
+			locks.remove(name);
+		}
+		true
+	}
+
+	/// Releases all locks still held by the workflow.
+	pub async fn release_locks(&self) {
+		for name in self.locks.lock().await.drain().map(|(name, _)| name) {
+			info!("Release lock `{}`", name);
+		}
+	}
+
 	pub async fn send_error(&self, err: Error) {
 		if let Err(send_err) = self.error_ch.lock().await.send(err.clone()).await {
 			error!(
@@ -117,4 +236,46 @@ impl Environment {
 	pub async fn get_ch(&self, id: &ChannelId) -> Option<Channel> {
 		self.channels.read().await.get(id).cloned()
 	}
+
+	/// Channels are kept until the workflow ends, unless they are removed.
+	pub async fn remove_ch(&self, id: &ChannelId) {
+		self.channels.write().await.remove(id);
+	}
+}
+
+/// Router action in progress, which is canceled at the router if the guard is
+/// dropped before the action finished, e.g. because its task was aborted.
+pub struct ActionGuard {
+	env: Arc<Environment>,
+	action_id: u64,
+	done: bool,
+}
+
+impl ActionGuard {
+	pub fn finish(mut self) {
+		self.done = true;
+		self.env.actions.lock().unwrap().remove(&self.action_id);
+	}
+
+	/// Cancels the action at the router and waits for the router to confirm it.
+	pub async fn cancel(mut self) {
+		self.done = true;
+		self.env.cancel_action(self.action_id).await;
+	}
+}
+
+impl Drop for ActionGuard {
+	fn drop(&mut self) {
+		if self.done {
+			return;
+		}
+
+		let env = self.env.clone();
+		let action_id = self.action_id;
+		let handle = tokio::spawn(async move { env.cancel_action(action_id).await });
+
+		let mut cancels = self.env.cancels.lock().unwrap();
+		cancels.retain(|handle| !handle.is_finished());
+		cancels.push(handle);
+	}
 }
@@ -1,4 +1,5 @@
 use ast::{Identifier, Span, Variable};
+use router::{RouterError, RouterStatus, TargetError};
 
 #[derive(Debug, Clone)]
 pub struct Error {
@@ -24,13 +25,16 @@ impl Error {
 
 #[derive(Debug, Clone)]
 pub enum ErrorKind {
+	ActionFailed { status: RouterStatus },
 	ArityMismatch { expected: usize, given: usize },
 	DivisionByZero,
 	Fatal(String),
+	InvalidTarget(TargetError),
 	InvalidType { msg: String },
 	MissingArgument { id: Identifier },
 	OrderCancel, // TODO: should be no error
 	OrderDone,   // TODO: should be no error
+	Router(RouterError),
 	UnknownArgument { id: Identifier },
 	VariableAlreadyInUse { id: Identifier },
 	VariableNotFound { id: Variable },
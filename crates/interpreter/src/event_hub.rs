@@ -0,0 +1,90 @@
+use std::{
+	collections::HashMap,
+	sync::{
+		Arc, LazyLock, Mutex,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+
+use crate::{Channel, ChannelId, Environment, Value};
+
+/// Named events waited for by the workflows running in this process.
+pub static EVENTS: LazyLock<EventHub> = LazyLock::new(EventHub::default);
+
+#[derive(Default)]
+pub struct EventHub {
+	waiting: Mutex<HashMap<String, Vec<(u64, Channel)>>>,
+	subscription_id: AtomicU64,
+}
+
+/// Registration of a waiting channel, which is removed from the hub
+/// and from the environment of the workflow when dropped.
+pub struct Subscription {
+	hub: &'static EventHub,
+	name: String,
+	id: u64,
+	env: Arc<Environment>,
+	ch_id: ChannelId,
+}
+
+impl EventHub {
+	/// Creates a channel in the environment, which receives the payload once the event is delivered.
+	pub async fn subscribe(
+		&'static self,
+		name: &str,
+		env: &Arc<Environment>,
+	) -> (Subscription, Channel) {
+		let (ch_id, ch) = env.create_ch(1).await;
+		let id = self.subscription_id.fetch_add(1, Ordering::Relaxed);
+		self.waiting
+			.lock()
+			.unwrap()
+			.entry(name.to_owned())
+			.or_default()
+			.push((id, ch.clone()));
+
+		let subscription = Subscription {
+			hub: self,
+			name: name.to_owned(),
+			id,
+			env: env.clone(),
+			ch_id,
+		};
+		(subscription, ch)
+	}
+
+	/// Returns the number of waiting channels which received the event.
+	pub async fn deliver(&self, name: &str, payload: Value) -> usize {
+		let waiting = self
+			.waiting
+			.lock()
+			.unwrap()
+			.remove(name)
+			.unwrap_or_default();
+
+		let mut delivered = 0;
+		for (_, ch) in waiting {
+			if ch.send(payload.clone()).await.is_some() {
+				delivered += 1;
+			}
+		}
+
+		delivered
+	}
+}
+
+impl Drop for Subscription {
+	fn drop(&mut self) {
+		let mut waiting = self.hub.waiting.lock().unwrap();
+		if let Some(channels) = waiting.get_mut(&self.name) {
+			channels.retain(|(id, _)| *id != self.id);
+			if channels.is_empty() {
+				waiting.remove(&self.name);
+			}
+		}
+
+		let env = self.env.clone();
+		let ch_id = self.ch_id.clone();
+		tokio::spawn(async move { env.remove_ch(&ch_id).await });
+	}
+}
@@ -3,9 +3,10 @@ use std::{collections::HashMap, sync::Arc};
 use log::{error, info};
 use tokio::{
 	select,
-	sync::mpsc::{self, Sender},
+	sync::mpsc::{self, Receiver, Sender},
 };
 
+use ::router::{RouterCapabilities, RouterClient};
 use ast::{Identifier, Workflow};
 
 mod error;
@@ -16,17 +17,23 @@ mod value;
 pub use value::*;
 mod router;
 pub use router::Router;
+mod run_options;
+pub use run_options::RunOptions;
 
 mod channel;
 use channel::Channel;
 mod declaration;
 mod environment;
 use environment::Environment;
+mod event_hub;
+use event_hub::EVENTS;
 mod expression;
 mod function_value;
 use function_value::FunctionValue;
 mod interrupt;
+mod lock_manager;
 use interrupt::Interrupt;
+use lock_manager::{LOCKS, LockError, LockPermit};
 mod scope;
 use scope::Scope;
 mod statement;
@@ -37,12 +44,48 @@ pub async fn run_workflow(
 	workflow: Workflow,
 	variables: HashMap<Identifier, Value>,
 	router: Router,
+	options: RunOptions,
 	user_log_ch: Sender<LogEntry>,
 ) -> Result<(), Error> {
-	let (err_tx, mut err_rx) = mpsc::channel(1);
+	if options.preflight {
+		let capabilities = check_router(&router).await?;
+		info!("Router capabilities: {:?}", capabilities);
+	}
+
+	let seed = options.seed.unwrap_or_else(rand::random);
+	info!("Random seed `{}`", seed);
+
+	let (err_tx, err_rx) = mpsc::channel(1);
 	let global_scope = Arc::new(Scope::new());
-	let env = Arc::new(Environment::new(global_scope, router, user_log_ch, err_tx));
+	let env = Arc::new(Environment::new(
+		global_scope,
+		router,
+		options.site,
+		user_log_ch,
+		err_tx,
+		seed,
+	));
+
+	// every exit goes through the cleanup below, including failed declarations
+	let ret = interpret_workflow(&workflow, &variables, &env, err_rx).await;
+
+	if ret.is_err() {
+		info!("Workflow stopped, cancel remaining background tasks and router actions!");
+		env.cancel().await;
+	}
+	env.release_vehicles().await;
+	env.release_locks().await;
 
+	ret
+}
+
+/// Declares the globals and functions and runs the actions of the workflow.
+async fn interpret_workflow(
+	workflow: &Workflow,
+	variables: &HashMap<Identifier, Value>,
+	env: &Arc<Environment>,
+	mut err_rx: Receiver<Error>,
+) -> Result<(), Error> {
 	// global declarations
 	for global_decl in &workflow.globals {
 		let mut default = None;
@@ -50,34 +93,25 @@ pub async fn run_workflow(
 			default = Some(val.clone());
 		}
 
-		declaration::interpret_global(global_decl, &env, default).await?;
+		declaration::interpret_global(global_decl, env, default).await?;
 	}
 
 	// function declarations
 	for fn_decl in &workflow.functions {
-		declaration::interpret_function(fn_decl, &env).await?;
+		declaration::interpret_function(fn_decl, env).await?;
 	}
 
-	let fut = declaration::interpret_actions(&workflow.actions, &env.global_scope, &env);
+	let fut = declaration::interpret_actions(&workflow.actions, &env.global_scope, env);
 
 	select! {
 		ret = fut => {
 			if ret.is_ok() {
 				err_rx.close();
 				info!("Main flow finished, error channel closed, waiting for background tasks to finish!");
-				while let Some(handle) = env.pop_handle().await {
-					if let Ok(val) = handle.await {
-						if let Err(err) = val {
-							info!("Background task returned error: {:?}", err);
-							return Err(err);
-						}
-					} else {
-						error!("Failed to finish background task!");
-					}
-				}
+				wait_for_background_tasks(env).await
+			} else {
+				ret
 			}
-
-			ret
 		},
 		val = err_rx.recv() => {
 			if let Some(err) = val {
@@ -89,3 +123,45 @@ pub async fn run_workflow(
 		}
 	}
 }
+
+/// Delivers the event to all `event::wait` calls of this process waiting for it
+/// and returns their number, events nobody is waiting for are dropped.
+pub async fn send_event(name: &str, payload: Value) -> usize {
+	EVENTS.deliver(name, payload).await
+}
+
+/// Checks that the router is reachable and ready to accept actions.
+pub async fn check_router(router: &Router) -> Result<RouterCapabilities, Error> {
+	let to_error = |err| Error {
+		kind: ErrorKind::Router(err),
+		span: None,
+	};
+
+	let health = router.health().await.map_err(to_error)?;
+	if !health.ready {
+		return Err(Error::fatal(format!(
+			"Router is not ready{}",
+			health
+				.message
+				.map(|msg| format!(", {}", msg))
+				.unwrap_or_default()
+		)));
+	}
+
+	router.capabilities().await.map_err(to_error)
+}
+
+async fn wait_for_background_tasks(env: &Environment) -> Result<(), Error> {
+	while let Some(handle) = env.pop_handle().await {
+		if let Ok(val) = handle.await {
+			if let Err(err) = val {
+				info!("Background task returned error: {:?}", err);
+				return Err(err);
+			}
+		} else {
+			error!("Failed to finish background task!");
+		}
+	}
+
+	Ok(())
+}
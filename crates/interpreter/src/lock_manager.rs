@@ -0,0 +1,91 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, LazyLock, Mutex},
+	time::Duration,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Named locks shared by all workflows running in this process.
+pub static LOCKS: LazyLock<LockManager> = LazyLock::new(LockManager::default);
+
+#[derive(Default)]
+pub struct LockManager {
+	locks: Mutex<HashMap<String, LockEntry>>,
+}
+
+struct LockEntry {
+	semaphore: Arc<Semaphore>,
+	limit: u32,
+	/// Number of holders and waiters, the entry is removed once it drops to zero.
+	users: usize,
+}
+
+#[derive(Debug)]
+pub enum LockError {
+	LimitMismatch { limit: u32 },
+	Timeout,
+}
+
+/// Held lock, the lock is released when the permit is dropped.
+pub struct LockPermit {
+	_permit: OwnedSemaphorePermit,
+	_user: LockUser,
+}
+
+/// Registration of a holder or waiter of a lock.
+struct LockUser {
+	manager: &'static LockManager,
+	name: String,
+}
+
+impl LockManager {
+	/// Waits until one of the `limit` permits of the lock is free.
+	/// The limit of a lock is fixed by its first user until the lock is unused again.
+	pub async fn acquire(
+		&'static self,
+		name: &str,
+		limit: u32,
+		timeout: Duration,
+	) -> Result<LockPermit, LockError> {
+		let (semaphore, user) = {
+			let mut locks = self.locks.lock().unwrap();
+			let entry = locks.entry(name.to_owned()).or_insert_with(|| LockEntry {
+				semaphore: Arc::new(Semaphore::new(limit as usize)),
+				limit,
+				users: 0,
+			});
+			if entry.limit != limit {
+				return Err(LockError::LimitMismatch { limit: entry.limit });
+			}
+			entry.users += 1;
+
+			let user = LockUser {
+				manager: self,
+				name: name.to_owned(),
+			};
+			(entry.semaphore.clone(), user)
+		};
+
+		match tokio::time::timeout(timeout, semaphore.acquire_owned()).await {
+			Ok(Ok(permit)) => Ok(LockPermit {
+				_permit: permit,
+				_user: user,
+			}),
+			// the semaphore is never closed
+			Ok(Err(_)) | Err(_) => Err(LockError::Timeout),
+		}
+	}
+}
+
+impl Drop for LockUser {
+	fn drop(&mut self) {
+		let mut locks = self.manager.locks.lock().unwrap();
+		if let Some(entry) = locks.get_mut(&self.name) {
+			entry.users -= 1;
+			if entry.users == 0 {
+				locks.remove(&self.name);
+			}
+		}
+	}
+}
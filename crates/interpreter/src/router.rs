@@ -1,29 +1,84 @@
-use router::{RouterClient, RouterClientGrpc, RouterClientWs};
+use tokio::sync::mpsc::Sender;
+
+use router::{
+	ActionRequest, RouterCapabilities, RouterClient, RouterClientGrpc, RouterClientHttp,
+	RouterClientVda5050, RouterClientWs, RouterError, RouterHealth, RouterProgress, RouterResponse,
+	Vehicle, VehicleSelector,
+};
 
 pub enum Router {
 	Grpc(RouterClientGrpc),
+	Http(RouterClientHttp),
 	Ws(RouterClientWs),
+	Vda5050(Box<RouterClientVda5050>),
 }
 
 impl RouterClient for Router {
-	async fn pickup(&self, target: router::Target) -> Option<router::RouterStatus> {
+	fn next_action_id(&self) -> u64 {
+		match self {
+			Router::Grpc(router) => router.next_action_id(),
+			Router::Http(router) => router.next_action_id(),
+			Router::Ws(router) => router.next_action_id(),
+			Router::Vda5050(router) => router.next_action_id(),
+		}
+	}
+
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		match self {
+			Router::Grpc(router) => router.execute(action_id, action, progress).await,
+			Router::Http(router) => router.execute(action_id, action, progress).await,
+			Router::Ws(router) => router.execute(action_id, action, progress).await,
+			Router::Vda5050(router) => router.execute(action_id, action, progress).await,
+		}
+	}
+
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		match self {
+			Router::Grpc(router) => router.cancel(action_id).await,
+			Router::Http(router) => router.cancel(action_id).await,
+			Router::Ws(router) => router.cancel(action_id).await,
+			Router::Vda5050(router) => router.cancel(action_id).await,
+		}
+	}
+
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		match self {
+			Router::Grpc(router) => router.reserve(selector).await,
+			Router::Http(router) => router.reserve(selector).await,
+			Router::Ws(router) => router.reserve(selector).await,
+			Router::Vda5050(router) => router.reserve(selector).await,
+		}
+	}
+
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError> {
 		match self {
-			Router::Grpc(router) => router.pickup(target).await,
-			Router::Ws(router) => router.pickup(target).await,
+			Router::Grpc(router) => router.release(vehicle).await,
+			Router::Http(router) => router.release(vehicle).await,
+			Router::Ws(router) => router.release(vehicle).await,
+			Router::Vda5050(router) => router.release(vehicle).await,
 		}
 	}
 
-	async fn drop(&self, target: router::Target) -> Option<router::RouterStatus> {
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
 		match self {
-			Router::Grpc(router) => router.drop(target).await,
-			Router::Ws(router) => router.drop(target).await,
+			Router::Grpc(router) => router.health().await,
+			Router::Http(router) => router.health().await,
+			Router::Ws(router) => router.health().await,
+			Router::Vda5050(router) => router.health().await,
 		}
 	}
 
-	async fn drive(&self, target: router::Target) -> Option<router::RouterStatus> {
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
 		match self {
-			Router::Grpc(router) => router.drive(target).await,
-			Router::Ws(router) => router.drive(target).await,
+			Router::Grpc(router) => router.capabilities().await,
+			Router::Http(router) => router.capabilities().await,
+			Router::Ws(router) => router.capabilities().await,
+			Router::Vda5050(router) => router.capabilities().await,
 		}
 	}
 }
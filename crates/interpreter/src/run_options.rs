@@ -0,0 +1,12 @@
+use site::Site;
+
+/// Options of a workflow run, `Default` runs without site and preflight check.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+	/// Targets are checked against the site if one is given.
+	pub site: Option<Site>,
+	/// The workflow only starts if the router is reachable and ready.
+	pub preflight: bool,
+	/// Random values are drawn deterministically from the seed, a random seed is used if none is given.
+	pub seed: Option<u64>,
+}
@@ -24,6 +24,57 @@ pub const fn id(name: &[u8]) -> u32 {
 		b"channel" => 9,
 		b"arg" => 10,
 		b"events" => 11,
+		b"name" => 12,
+		b"params" => 13,
+		b"timeout" => 14,
+		b"retry" => 15,
+		b"vehicle" => 16,
+		b"area" => 17,
+		b"from" => 18,
+		b"to" => 19,
+		b"limit" => 20,
+		b"x" => 21,
+		b"y" => 22,
+		b"min" => 23,
+		b"max" => 24,
+		b"string" => 25,
+		b"separator" => 26,
+		b"values" => 27,
+		b"pattern" => 28,
+		b"start" => 29,
+		b"end" => 30,
+		b"width" => 31,
+		b"fill" => 32,
+		b"side" => 33,
+		b"value" => 34,
+		b"decimals" => 35,
+		b"array" => 36,
+		b"callback" => 37,
+		b"initial" => 38,
+		b"comparator" => 39,
+		b"object" => 40,
+		b"key" => 41,
+		b"other" => 42,
+		b"deep" => 43,
+		b"pretty" => 44,
+		b"method" => 45,
+		b"headers" => 46,
+		b"query" => 47,
+		b"body" => 48,
+		b"auth" => 49,
+		b"content" => 50,
+		b"time" => 51,
+		b"format" => 52,
+		b"timezone" => 53,
+		b"days" => 54,
+		b"hours" => 55,
+		b"minutes" => 56,
+		b"seconds" => 57,
+		b"count" => 58,
+		b"encoding" => 59,
+		b"algorithm" => 60,
+		b"message" => 61,
+		b"key_encoding" => 62,
 		_ => todo!(),
 	}
 }
@@ -41,6 +92,57 @@ pub const fn name(id: u32) -> &'static [u8] {
 		9 => b"channel",
 		10 => b"arg",
 		11 => b"events",
+		12 => b"name",
+		13 => b"params",
+		14 => b"timeout",
+		15 => b"retry",
+		16 => b"vehicle",
+		17 => b"area",
+		18 => b"from",
+		19 => b"to",
+		20 => b"limit",
+		21 => b"x",
+		22 => b"y",
+		23 => b"min",
+		24 => b"max",
+		25 => b"string",
+		26 => b"separator",
+		27 => b"values",
+		28 => b"pattern",
+		29 => b"start",
+		30 => b"end",
+		31 => b"width",
+		32 => b"fill",
+		33 => b"side",
+		34 => b"value",
+		35 => b"decimals",
+		36 => b"array",
+		37 => b"callback",
+		38 => b"initial",
+		39 => b"comparator",
+		40 => b"object",
+		41 => b"key",
+		42 => b"other",
+		43 => b"deep",
+		44 => b"pretty",
+		45 => b"method",
+		46 => b"headers",
+		47 => b"query",
+		48 => b"body",
+		49 => b"auth",
+		50 => b"content",
+		51 => b"time",
+		52 => b"format",
+		53 => b"timezone",
+		54 => b"days",
+		55 => b"hours",
+		56 => b"minutes",
+		57 => b"seconds",
+		58 => b"count",
+		59 => b"encoding",
+		60 => b"algorithm",
+		61 => b"message",
+		62 => b"key_encoding",
 		_ => todo!(),
 	}
 }
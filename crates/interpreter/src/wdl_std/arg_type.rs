@@ -1,6 +1,12 @@
-use router::Target;
+use router::{Coordinate, Target, Vehicle, VehicleSelector};
 use serde::Deserialize;
 
 pub trait ArgType<'de>: Deserialize<'de> {}
 
+impl ArgType<'_> for Coordinate {}
+
 impl ArgType<'_> for Target {}
+
+impl ArgType<'_> for Vehicle {}
+
+impl ArgType<'_> for VehicleSelector {}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::{ChannelId, Error, ErrorKind, FunctionId, Value, ValueType, wdl_std::ArgType};
 
 pub trait FromValue: Sized {
@@ -57,6 +59,34 @@ impl FromValue for String {
 	}
 }
 
+impl FromValue for Vec<Value> {
+	fn from_value(val: Value) -> Result<Option<Self>, Error> {
+		if let Value::Array(val) = val {
+			Ok(Some(val))
+		} else {
+			Ok(None)
+		}
+	}
+
+	fn get_type() -> ValueType {
+		ValueType::Array
+	}
+}
+
+impl FromValue for HashMap<String, Value> {
+	fn from_value(val: Value) -> Result<Option<Self>, Error> {
+		if let Value::Object(val) = val {
+			Ok(Some(val))
+		} else {
+			Ok(None)
+		}
+	}
+
+	fn get_type() -> ValueType {
+		ValueType::Object
+	}
+}
+
 impl FromValue for FunctionId {
 	fn from_value(val: Value) -> Result<Option<Self>, Error> {
 		if let Value::Function(val) = val {
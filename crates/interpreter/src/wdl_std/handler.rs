@@ -16,6 +16,12 @@ impl_handler!(T1);
 impl_handler!(T1, T2);
 impl_handler!(T1, T2, T3);
 impl_handler!(T1, T2, T3, T4);
+impl_handler!(T1, T2, T3, T4, T5);
+impl_handler!(T1, T2, T3, T4, T5, T6);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
 
 macro_rules! impl_handler {
 	($($ty:ident),*) => {
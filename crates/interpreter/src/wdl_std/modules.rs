@@ -1,23 +1,55 @@
 use crate::{FunctionId, FunctionValue};
 
 mod action;
+mod array;
+mod base64;
 mod channel;
+mod crypto;
+mod event;
+mod fleet;
+mod hex;
 mod http;
+mod json;
+mod lock;
 mod log;
+mod math;
+mod object;
 mod order;
+mod random;
 mod regex;
+mod router;
+mod site;
+mod string;
 mod time;
+mod url;
+mod uuid;
 
 pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 	if let Some(module) = id.scope.first() {
 		return match module.id.as_str() {
 			"action" => action::resolve_id(id),
+			"array" => array::resolve_id(id),
 			"log" => log::resolve_id(id),
+			"base64" => base64::resolve_id(id),
+			"hex" => hex::resolve_id(id),
+			"url" => url::resolve_id(id),
+			"crypto" => crypto::resolve_id(id),
+			"math" => math::resolve_id(id),
 			"http" => http::resolve_id(id),
+			"json" => json::resolve_id(id),
+			"object" => object::resolve_id(id),
 			"time" => time::resolve_id(id),
 			"regex" => regex::resolve_id(id),
+			"string" => string::resolve_id(id),
 			"channel" => channel::resolve_id(id),
+			"event" => event::resolve_id(id),
+			"fleet" => fleet::resolve_id(id),
+			"lock" => lock::resolve_id(id),
 			"order" => order::resolve_id(id),
+			"random" => random::resolve_id(id),
+			"site" => site::resolve_id(id),
+			"router" => router::resolve_id(id),
+			"uuid" => uuid::resolve_id(id),
 			_ => None,
 		};
 	}
@@ -1,16 +1,22 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use log::info;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, Receiver};
 
 use ast::{Identifier, Span};
-use router::{self, RouterClient, RouterStatus, Target};
+use router::{
+	self, ActionRequest, RouterClient, RouterError, RouterProgress, RouterResponse, RouterStatus,
+	Target, Vehicle,
+};
 
 use crate::{
 	Environment, Error, ErrorKind, FunctionId, FunctionValue, LogEntry, Value,
-	wdl_std::{Arg, ArgType, call_function, get_handler, id},
+	wdl_std::{Arg, ArgType, IntoResult, ResultType, call_function, get_handler, id},
 };
 
+use super::time::{millis, to_duration};
+
 pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 	if id.scope.len() > 1 {
 		return None;
@@ -20,6 +26,7 @@ pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 		"pickup" => Some(get_handler(pickup)),
 		"drop" => Some(get_handler(drop)),
 		"drive" => Some(get_handler(drive)),
+		"execute" => Some(get_handler(execute)),
 		_ => None,
 	}
 }
@@ -28,216 +35,494 @@ pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 #[serde(deny_unknown_fields)]
 struct Events {
 	pub no_station_left: Option<FunctionId>,
+	pub path_blocked: Option<FunctionId>,
+	pub load_handling_failed: Option<FunctionId>,
+	pub battery_low: Option<FunctionId>,
+	pub emergency_stop: Option<FunctionId>,
+	pub timeout: Option<FunctionId>,
+	pub progress: Option<FunctionId>,
 }
 
 impl ArgType<'_> for Events {}
 
-async fn pickup(
-	target: Arg<Target, { id(b"target") }>,
-	events: Option<Arg<Events, { id(b"events") }>>,
-	fn_span: Span,
-	env: Arc<Environment>,
-) -> Result<(), Error> {
-	info!("pickup from {:?}", target.val);
+/// Event triggered by the status of a failed action.
+struct StatusEvent<'a> {
+	name: &'static str,
+	type_: &'static str,
+	callback: Option<&'a FunctionId>,
+}
 
-	env.send_log(LogEntry::info("Start pickup action.", Some(fn_span)))
-		.await;
+impl Events {
+	/// Maps the status to its event and the callback hooked for it, `Done` triggers no event.
+	fn get_callback<'a>(
+		events: Option<&'a Self>,
+		status: &RouterStatus,
+	) -> Option<StatusEvent<'a>> {
+		let (name, type_, callback) = match status {
+			RouterStatus::Done => return None,
+			RouterStatus::NoStationLeft => (
+				"no_station_left",
+				"NoStationLeftEvent",
+				events.and_then(|events| events.no_station_left.as_ref()),
+			),
+			RouterStatus::PathBlocked => (
+				"path_blocked",
+				"PathBlockedEvent",
+				events.and_then(|events| events.path_blocked.as_ref()),
+			),
+			RouterStatus::LoadHandlingFailed => (
+				"load_handling_failed",
+				"LoadHandlingFailedEvent",
+				events.and_then(|events| events.load_handling_failed.as_ref()),
+			),
+			RouterStatus::BatteryLow => (
+				"battery_low",
+				"BatteryLowEvent",
+				events.and_then(|events| events.battery_low.as_ref()),
+			),
+			RouterStatus::EmergencyStop => (
+				"emergency_stop",
+				"EmergencyStopEvent",
+				events.and_then(|events| events.emergency_stop.as_ref()),
+			),
+			RouterStatus::Timeout => (
+				"timeout",
+				"TimeoutEvent",
+				events.and_then(|events| events.timeout.as_ref()),
+			),
+		};
+
+		Some(StatusEvent {
+			name,
+			type_,
+			callback,
+		})
+	}
+}
 
-	let status = match env.router.pickup(target.val).await {
-		Some(s) => s,
-		None => {
-			return Err(Error {
-				kind: ErrorKind::Fatal("Communication with router failed".to_owned()),
-				span: Some(fn_span),
-			});
-		}
-	};
+#[derive(Debug, Serialize)]
+struct ProgressEvent {
+	#[serde(rename = "type")]
+	type_: &'static str,
+	#[serde(flatten)]
+	progress: RouterProgress,
+}
 
-	info!("pickup status: `{:?}`", status);
+impl ResultType for ProgressEvent {}
 
-	let mut hooked = false;
-	if let Some(events) = events {
-		if status == RouterStatus::NoStationLeft {
-			hooked = true;
+impl ResultType for RouterResponse {}
 
-			if let Some(callback) = events.val.no_station_left {
-				env.send_log(LogEntry::info(
-					format!("Trigger `no_station_left`, execute `{}`.", callback),
-					Some(fn_span),
-				))
-				.await;
+#[derive(Debug, Deserialize)]
+struct Params(serde_json::Map<String, serde_json::Value>);
 
-				let mut event = HashMap::new();
-				event.insert(
-					"type".to_owned(),
-					Value::String("NoStationLeftEvent".to_owned()),
-				);
-				let ret = call_function(
-					&callback,
-					vec![Value::Object(event)],
-					Identifier {
-						id: "no_station_left".to_owned(),
-					},
-					events.span,
-					&env,
-				)
-				.await?;
-
-				info!("Return value of no_station_left handler: {:?}", ret);
-			}
+impl ArgType<'_> for Params {}
+
+/// `attempts` includes the first execution, without `on` all failures are retried.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetryPolicy {
+	pub attempts: f64,
+	#[serde(default)]
+	pub backoff_ms: f64,
+	pub on: Option<Vec<RetryOn>>,
+}
+
+impl ArgType<'_> for RetryPolicy {}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RetryOn {
+	NoStationLeft,
+	PathBlocked,
+	LoadHandlingFailed,
+	BatteryLow,
+	EmergencyStop,
+	Timeout,
+	RouterError,
+}
+
+impl RetryPolicy {
+	/// Returns the number of attempts and the backoff, fails at `span` if one is invalid.
+	fn check(&self, span: Span) -> Result<(u32, Duration), Error> {
+		let attempts = self.attempts;
+		if attempts.fract() != 0.0 || !(1.0..=u32::MAX as f64).contains(&attempts) {
+			return Err(Error::positional(
+				format!(
+					"The number of attempts must be a whole number >=1, but `{}` given",
+					attempts
+				),
+				span,
+			));
 		}
-	}
 
-	if !hooked && status == RouterStatus::NoStationLeft {
-		env.send_log(LogEntry::info(
-			"Trigger `no_station_left`, not hooked.",
-			Some(fn_span),
-		))
-		.await;
+		Ok((attempts as u32, to_duration(self.backoff_ms, span)?))
 	}
 
-	env.send_log(LogEntry::info("Pickup action finished.", Some(fn_span)))
-		.await;
+	fn should_retry(&self, response: &Result<RouterResponse, Error>) -> bool {
+		let reason = match response {
+			Ok(response) => match response.status {
+				RouterStatus::Done => return false,
+				RouterStatus::NoStationLeft => RetryOn::NoStationLeft,
+				RouterStatus::PathBlocked => RetryOn::PathBlocked,
+				RouterStatus::LoadHandlingFailed => RetryOn::LoadHandlingFailed,
+				RouterStatus::BatteryLow => RetryOn::BatteryLow,
+				RouterStatus::EmergencyStop => RetryOn::EmergencyStop,
+				RouterStatus::Timeout => RetryOn::Timeout,
+			},
+			Err(Error {
+				kind: ErrorKind::Router(_),
+				..
+			}) => RetryOn::RouterError,
+			Err(_) => return false,
+		};
+
+		self.on.as_ref().is_none_or(|on| on.contains(&reason))
+	}
+}
 
-	Ok(())
+async fn pickup(
+	target: Arg<Target, { id(b"target") }>,
+	vehicle: Option<Arg<Vehicle, { id(b"vehicle") }>>,
+	events: Option<Arg<Events, { id(b"events") }>>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	retry: Option<Arg<RetryPolicy, { id(b"retry") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	execute_action(
+		ActionRequest::new("pickup", Some(check_target(target, &env)?)),
+		vehicle,
+		events,
+		timeout,
+		retry,
+		fn_span,
+		env,
+	)
+	.await
 }
 
 async fn drop(
 	target: Arg<Target, { id(b"target") }>,
+	vehicle: Option<Arg<Vehicle, { id(b"vehicle") }>>,
 	events: Option<Arg<Events, { id(b"events") }>>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	retry: Option<Arg<RetryPolicy, { id(b"retry") }>>,
 	fn_span: Span,
 	env: Arc<Environment>,
-) -> Result<(), Error> {
-	info!("drop to {:?}", target.val);
+) -> Result<RouterResponse, Error> {
+	execute_action(
+		ActionRequest::new("drop", Some(check_target(target, &env)?)),
+		vehicle,
+		events,
+		timeout,
+		retry,
+		fn_span,
+		env,
+	)
+	.await
+}
 
-	env.send_log(LogEntry::info("Start drop action.", Some(fn_span)))
-		.await;
+async fn drive(
+	target: Arg<Target, { id(b"target") }>,
+	vehicle: Option<Arg<Vehicle, { id(b"vehicle") }>>,
+	events: Option<Arg<Events, { id(b"events") }>>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	retry: Option<Arg<RetryPolicy, { id(b"retry") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	execute_action(
+		ActionRequest::new("drive", Some(check_target(target, &env)?)),
+		vehicle,
+		events,
+		timeout,
+		retry,
+		fn_span,
+		env,
+	)
+	.await
+}
 
-	let status = match env.router.drop(target.val).await {
-		Some(s) => s,
-		None => {
-			return Err(Error {
-				kind: ErrorKind::Fatal("Communication with router failed".to_owned()),
-				span: Some(fn_span),
-			});
-		}
+#[allow(clippy::too_many_arguments)]
+async fn execute(
+	name: Arg<String, { id(b"name") }>,
+	target: Option<Arg<Target, { id(b"target") }>>,
+	params: Option<Arg<Params, { id(b"params") }>>,
+	vehicle: Option<Arg<Vehicle, { id(b"vehicle") }>>,
+	events: Option<Arg<Events, { id(b"events") }>>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	retry: Option<Arg<RetryPolicy, { id(b"retry") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	let target = match target {
+		Some(target) => Some(check_target(target, &env)?),
+		None => None,
 	};
+	let mut action = ActionRequest::new(name.val, target);
+	if let Some(params) = params {
+		action.params = params.val.0;
+	}
 
-	info!("drop status: `{:?}`", status);
+	execute_action(action, vehicle, events, timeout, retry, fn_span, env).await
+}
 
-	let mut hooked = false;
-	if let Some(events) = events {
-		if status == RouterStatus::NoStationLeft {
-			hooked = true;
+/// Normalizes the target and rejects it at the argument span if it is invalid.
+fn check_target(
+	target: Arg<Target, { id(b"target") }>,
+	env: &Environment,
+) -> Result<Target, Error> {
+	let span = target.span;
+	let target = target.val.normalize();
+	if let Err(err) = target.validate(env.site.as_ref()) {
+		return Err(Error {
+			kind: ErrorKind::InvalidTarget(err),
+			span: Some(span),
+		});
+	}
 
-			if let Some(callback) = events.val.no_station_left {
-				env.send_log(LogEntry::info(
-					format!("Trigger `no_station_left`, execute `{}`.", callback),
-					Some(fn_span),
-				))
-				.await;
+	Ok(target)
+}
 
-				let mut event = HashMap::new();
-				event.insert(
-					"type".to_owned(),
-					Value::String("NoStationLeftEvent".to_owned()),
-				);
-				let ret = call_function(
-					&callback,
-					vec![Value::Object(event)],
-					Identifier {
-						id: "no_station_left".to_owned(),
-					},
-					events.span,
-					&env,
-				)
-				.await?;
-
-				info!("Return value of no_station_left handler: {:?}", ret);
-			}
+/// Sends the action to the router and handles the returned status.  
+/// Failed attempts are repeated as long as the retry policy allows it,
+/// only the result of the last attempt is handled.
+async fn execute_action(
+	mut action: ActionRequest,
+	vehicle: Option<Arg<Vehicle, { id(b"vehicle") }>>,
+	events: Option<Arg<Events, { id(b"events") }>>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	retry: Option<Arg<RetryPolicy, { id(b"retry") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	let timeout = timeout.as_ref().map(millis).transpose()?;
+	let (retry, attempts, backoff) = match retry {
+		Some(retry) => {
+			let (attempts, backoff) = retry.val.check(retry.span)?;
+			(retry.val, attempts, backoff)
+		}
+		None => (RetryPolicy::default(), 1, Duration::ZERO),
+	};
+
+	action.vehicle = vehicle.map(|vehicle| vehicle.val.id);
+	let name = action.name.clone();
+	info!("{} at {:?}", name, action.target);
+
+	let mut attempt = 1;
+	let response = loop {
+		let mut msg = format!("Start {} action", name);
+		if attempts > 1 {
+			msg += &format!(" (attempt {}/{})", attempt, attempts);
+		}
+		env.send_log(LogEntry::info(msg + ".", Some(fn_span))).await;
+
+		let response =
+			execute_attempt(action.clone(), events.as_ref(), timeout, fn_span, &env).await;
+		if attempt >= attempts || !retry.should_retry(&response) {
+			break response?;
 		}
-	}
 
-	if !hooked && status == RouterStatus::NoStationLeft {
-		env.send_log(LogEntry::info(
-			"Trigger `no_station_left`, not hooked.",
+		let reason = match &response {
+			Ok(response) => format!("`{:?}`", response.status),
+			Err(Error {
+				kind: ErrorKind::Router(err),
+				..
+			}) => err.to_string(),
+			Err(err) => format!("{:?}", err.kind),
+		};
+		env.send_log(LogEntry::warn(
+			format!(
+				"Attempt {}/{} of {} action failed with {}, retry in {} ms.",
+				attempt, attempts, name, reason, retry.backoff_ms
+			),
 			Some(fn_span),
 		))
 		.await;
-	}
 
-	env.send_log(LogEntry::info("Drop action finished.", Some(fn_span)))
-		.await;
+		tokio::time::sleep(backoff).await;
+		attempt += 1;
+	};
 
-	Ok(())
+	info!("{} response: `{:?}`", name, response);
+
+	handle_status(&response.status, events.as_ref(), fn_span, &env).await?;
+
+	env.send_log(LogEntry::info(
+		format!("{} action finished.", capitalize(&name)),
+		Some(fn_span),
+	))
+	.await;
+
+	Ok(response)
 }
 
-async fn drive(
-	target: Arg<Target, { id(b"target") }>,
-	events: Option<Arg<Events, { id(b"events") }>>,
-	fn_span: Span,
-	env: Arc<Environment>,
-) -> Result<(), Error> {
-	info!("drive to {:?}", target.val);
+/// Upper cases the first character, e.g. for `Pickup action finished.`
+fn capitalize(name: &str) -> String {
+	let mut chars = name.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
 
-	env.send_log(LogEntry::info("Start drive action.", Some(fn_span)))
-		.await;
+/// Executes the action once, an action exceeding the timeout is canceled
+/// at the router and reported with the status `Timeout`.
+async fn execute_attempt(
+	action: ActionRequest,
+	events: Option<&Arg<Events, { id(b"events") }>>,
+	timeout: Option<Duration>,
+	fn_span: Span,
+	env: &Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	let action_id = env.router.next_action_id();
+	let (progress_tx, progress_rx) = mpsc::channel(10);
+	let guard = env.start_action(action_id);
+	let response = wait_for_router(
+		env.router.execute(action_id, action, progress_tx),
+		progress_rx,
+		events,
+		fn_span,
+		env,
+	);
+
+	let response = match timeout {
+		Some(timeout) => match tokio::time::timeout(timeout, response).await {
+			Ok(response) => response,
+			Err(_) => {
+				env.send_log(LogEntry::warn(
+					format!(
+						"Action timed out after {} ms, cancel it.",
+						timeout.as_millis()
+					),
+					Some(fn_span),
+				))
+				.await;
 
-	let status = match env.router.drive(target.val).await {
-		Some(s) => s,
-		None => {
-			return Err(Error {
-				kind: ErrorKind::Fatal("Communication with router failed".to_owned()),
-				span: Some(fn_span),
-			});
-		}
+				guard.cancel().await;
+				return Ok(RouterStatus::Timeout.into());
+			}
+		},
+		None => response.await,
 	};
+	guard.finish();
 
-	info!("drive status: `{:?}`", status);
+	response
+}
 
-	let mut hooked = false;
-	if let Some(events) = events {
-		if status == RouterStatus::NoStationLeft {
-			hooked = true;
+/// Executes the callback hooked for the returned status of an action.  
+/// If not hooked, `no_station_left` and `battery_low` are only logged,
+/// all other failures stop the workflow.
+async fn handle_status(
+	status: &RouterStatus,
+	events: Option<&Arg<Events, { id(b"events") }>>,
+	fn_span: Span,
+	env: &Arc<Environment>,
+) -> Result<(), Error> {
+	let Some(StatusEvent {
+		name: event_name,
+		type_: event_type,
+		callback,
+	}) = Events::get_callback(events.map(|events| &events.val), status)
+	else {
+		return Ok(());
+	};
 
-			if let Some(callback) = events.val.no_station_left {
+	let (Some(callback), Some(events)) = (callback, events) else {
+		return match status {
+			RouterStatus::NoStationLeft | RouterStatus::BatteryLow => {
 				env.send_log(LogEntry::info(
-					format!("Trigger `no_station_left`, execute `{}`.", callback),
+					format!("Trigger `{}`, not hooked.", event_name),
 					Some(fn_span),
 				))
 				.await;
 
-				let mut event = HashMap::new();
-				event.insert(
-					"type".to_owned(),
-					Value::String("NoStationLeftEvent".to_owned()),
-				);
-				let ret = call_function(
-					&callback,
-					vec![Value::Object(event)],
-					Identifier {
-						id: "no_station_left".to_owned(),
-					},
-					events.span,
-					&env,
-				)
-				.await?;
-
-				info!("Return value of no_station_left handler: {:?}", ret);
+				Ok(())
 			}
+			_ => Err(Error {
+				kind: ErrorKind::ActionFailed {
+					status: status.clone(),
+				},
+				span: Some(fn_span),
+			}),
+		};
+	};
+
+	env.send_log(LogEntry::info(
+		format!("Trigger `{}`, execute `{}`.", event_name, callback),
+		Some(fn_span),
+	))
+	.await;
+
+	let mut event = HashMap::new();
+	event.insert("type".to_owned(), Value::String(event_type.to_owned()));
+	let ret = call_function(
+		callback,
+		vec![Value::Object(event)],
+		Identifier {
+			id: event_name.to_owned(),
+		},
+		events.span,
+		env,
+	)
+	.await?;
+
+	info!("Return value of {} handler: {:?}", event_name, ret);
+
+	Ok(())
+}
+
+/// Waits until the router finished the action and forwards all progress
+/// updates in the meantime to the user log and the `progress` callback.
+async fn wait_for_router(
+	action: impl Future<Output = Result<RouterResponse, RouterError>>,
+	mut progress_rx: Receiver<RouterProgress>,
+	events: Option<&Arg<Events, { id(b"events") }>>,
+	fn_span: Span,
+	env: &Arc<Environment>,
+) -> Result<RouterResponse, Error> {
+	let progress = async {
+		while let Some(progress) = progress_rx.recv().await {
+			env.send_log(LogEntry::info(
+				format!("Progress: {}.", progress),
+				Some(fn_span),
+			))
+			.await;
+
+			let Some(events) = events else {
+				continue;
+			};
+			let Some(callback) = &events.val.progress else {
+				continue;
+			};
+
+			let event = ProgressEvent {
+				type_: "ProgressEvent",
+				progress,
+			}
+			.into_result()?;
+			let ret = call_function(
+				callback,
+				vec![event],
+				Identifier {
+					id: "progress".to_owned(),
+				},
+				events.span,
+				env,
+			)
+			.await?;
+
+			info!("Return value of progress handler: {:?}", ret);
 		}
-	}
 
-	if !hooked && status == RouterStatus::NoStationLeft {
-		env.send_log(LogEntry::info(
-			"Trigger `no_station_left`, not hooked.",
-			Some(fn_span),
-		))
-		.await;
-	}
+		Ok::<(), Error>(())
+	};
 
-	env.send_log(LogEntry::info("Drive action finished.", Some(fn_span)))
-		.await;
+	let (status, progress) = tokio::join!(action, progress);
+	progress?;
 
-	Ok(())
+	status.map_err(|err| Error {
+		kind: ErrorKind::Router(err),
+		span: Some(fn_span),
+	})
 }
@@ -0,0 +1,302 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use ast::Identifier;
+
+use crate::{
+	Environment, Error, ErrorKind, FunctionId, FunctionValue, Value,
+	wdl_std::{Arg, call_function, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"len" => Some(get_handler(len)),
+		"push" => Some(get_handler(push)),
+		"pop" => Some(get_handler(pop)),
+		"slice" => Some(get_handler(slice)),
+		"contains" => Some(get_handler(contains)),
+		"index_of" => Some(get_handler(index_of)),
+		"sort" => Some(get_handler(sort)),
+		"reverse" => Some(get_handler(reverse)),
+		"unique" => Some(get_handler(unique)),
+		"map" => Some(get_handler(map)),
+		"filter" => Some(get_handler(filter)),
+		"reduce" => Some(get_handler(reduce)),
+		"find" => Some(get_handler(find)),
+		"any" => Some(get_handler(any)),
+		"all" => Some(get_handler(all)),
+		_ => None,
+	}
+}
+
+type ArrayArg = Arg<Vec<Value>, { id(b"array") }>;
+type CallbackArg = Arg<FunctionId, { id(b"callback") }>;
+
+/// Calls the callback with the given arguments, errors inside the callback
+/// are reported at the callback argument.
+async fn call<const N: u32>(
+	callback: &Arg<FunctionId, N>,
+	name: &str,
+	args: Vec<Value>,
+	env: &Arc<Environment>,
+) -> Result<Value, Error> {
+	let name = Identifier {
+		id: name.to_owned(),
+	};
+
+	call_function(&callback.val, args, name, callback.span, env).await
+}
+
+/// Calls a callback which has to decide about the element.
+async fn predicate(
+	callback: &CallbackArg,
+	name: &str,
+	args: Vec<Value>,
+	env: &Arc<Environment>,
+) -> Result<bool, Error> {
+	match call(callback, name, args, env).await? {
+		Value::Bool(val) => Ok(val),
+		other => Err(Error {
+			kind: ErrorKind::InvalidType {
+				msg: format!(
+					"callback for `{}` returned `{}` instead of `bool`",
+					name,
+					other.get_type()
+				),
+			},
+			span: Some(callback.span),
+		}),
+	}
+}
+
+/// Converts a number argument into an index.
+fn index<const N: u32>(arg: &Arg<f64, N>, what: &str) -> Result<usize, Error> {
+	if arg.val < 0.0 || arg.val.fract() != 0.0 || !arg.val.is_finite() {
+		return Err(Error::positional(
+			format!("{} must be a non-negative integer", what),
+			arg.span,
+		));
+	}
+
+	Ok(arg.val as usize)
+}
+
+async fn len(array: ArrayArg) -> f64 {
+	array.val.len() as f64
+}
+
+/// Arrays are values, so the changed array is returned.
+async fn push(array: ArrayArg, value: Arg<Value, { id(b"value") }>) -> Vec<Value> {
+	let mut array = array.val;
+	array.push(value.val);
+	array
+}
+
+/// Returns the array without its last element.
+async fn pop(array: ArrayArg) -> Vec<Value> {
+	let mut array = array.val;
+	array.pop();
+	array
+}
+
+/// Returns the elements from `start` up to, but not including, `end`.
+async fn slice(
+	array: ArrayArg,
+	start: Arg<f64, { id(b"start") }>,
+	end: Option<Arg<f64, { id(b"end") }>>,
+) -> Result<Vec<Value>, Error> {
+	let len = array.val.len();
+	let start_val = index(&start, "Start")?;
+	let end_val = match &end {
+		Some(end) => index(end, "End")?,
+		None => len,
+	};
+	if start_val > end_val || end_val > len {
+		return Err(Error::positional(
+			format!(
+				"Range {}..{} is out of bounds for an array of length {}",
+				start_val, end_val, len
+			),
+			end.as_ref().map_or(start.span, |end| end.span),
+		));
+	}
+
+	Ok(array.val[start_val..end_val].to_vec())
+}
+
+async fn contains(array: ArrayArg, value: Arg<Value, { id(b"value") }>) -> bool {
+	array.val.contains(&value.val)
+}
+
+/// Returns `null` if the value is not part of the array.
+async fn index_of(array: ArrayArg, value: Arg<Value, { id(b"value") }>) -> Option<f64> {
+	array
+		.val
+		.iter()
+		.position(|element| *element == value.val)
+		.map(|idx| idx as f64)
+}
+
+/// Without comparator numbers and strings are sorted ascending.
+/// The comparator returns a negative number, zero or a positive number
+/// if the first argument is less than, equal to or greater than the second.
+async fn sort(
+	array: ArrayArg,
+	comparator: Option<Arg<FunctionId, { id(b"comparator") }>>,
+	env: Arc<Environment>,
+) -> Result<Vec<Value>, Error> {
+	let compare = async |a: &Value, b: &Value| -> Result<Ordering, Error> {
+		let Some(comparator) = &comparator else {
+			return match (a, b) {
+				(Value::Number(a), Value::Number(b)) => Ok(a.total_cmp(b)),
+				(Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+				_ => Err(Error {
+					kind: ErrorKind::InvalidType {
+						msg: format!(
+							"cannot compare `{}` and `{}` without comparator",
+							a.get_type(),
+							b.get_type()
+						),
+					},
+					span: Some(array.span),
+				}),
+			};
+		};
+
+		match call(comparator, "sort", vec![a.clone(), b.clone()], &env).await? {
+			Value::Number(order) => Ok(order.partial_cmp(&0.0).unwrap_or(Ordering::Equal)),
+			other => Err(Error {
+				kind: ErrorKind::InvalidType {
+					msg: format!(
+						"comparator returned `{}` instead of `number`",
+						other.get_type()
+					),
+				},
+				span: Some(comparator.span),
+			}),
+		}
+	};
+
+	// binary insertion sort, stable and only O(n log n) comparator calls
+	let mut sorted: Vec<Value> = Vec::with_capacity(array.val.len());
+	for element in &array.val {
+		let (mut low, mut high) = (0, sorted.len());
+		while low < high {
+			let mid = (low + high) / 2;
+			if compare(element, &sorted[mid]).await? == Ordering::Less {
+				high = mid;
+			} else {
+				low = mid + 1;
+			}
+		}
+		sorted.insert(low, element.clone());
+	}
+
+	Ok(sorted)
+}
+
+async fn reverse(array: ArrayArg) -> Vec<Value> {
+	let mut array = array.val;
+	array.reverse();
+	array
+}
+
+/// Keeps the first occurrence of each element.
+async fn unique(array: ArrayArg) -> Vec<Value> {
+	let mut unique = Vec::new();
+	for element in array.val {
+		if !unique.contains(&element) {
+			unique.push(element);
+		}
+	}
+
+	unique
+}
+
+/// The callback is called with the element and its index.
+async fn map(
+	array: ArrayArg,
+	callback: CallbackArg,
+	env: Arc<Environment>,
+) -> Result<Vec<Value>, Error> {
+	let mut mapped = Vec::with_capacity(array.val.len());
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![element, Value::Number(idx as f64)];
+		mapped.push(call(&callback, "map", args, &env).await?);
+	}
+
+	Ok(mapped)
+}
+
+async fn filter(
+	array: ArrayArg,
+	callback: CallbackArg,
+	env: Arc<Environment>,
+) -> Result<Vec<Value>, Error> {
+	let mut filtered = Vec::new();
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![element.clone(), Value::Number(idx as f64)];
+		if predicate(&callback, "filter", args, &env).await? {
+			filtered.push(element);
+		}
+	}
+
+	Ok(filtered)
+}
+
+/// The callback is called with the accumulator, the element and its index.
+async fn reduce(
+	array: ArrayArg,
+	callback: CallbackArg,
+	initial: Arg<Value, { id(b"initial") }>,
+	env: Arc<Environment>,
+) -> Result<Value, Error> {
+	let mut acc = initial.val;
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![acc, element, Value::Number(idx as f64)];
+		acc = call(&callback, "reduce", args, &env).await?;
+	}
+
+	Ok(acc)
+}
+
+/// Returns the first matching element or `null`.
+async fn find(
+	array: ArrayArg,
+	callback: CallbackArg,
+	env: Arc<Environment>,
+) -> Result<Value, Error> {
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![element.clone(), Value::Number(idx as f64)];
+		if predicate(&callback, "find", args, &env).await? {
+			return Ok(element);
+		}
+	}
+
+	Ok(Value::Null)
+}
+
+async fn any(array: ArrayArg, callback: CallbackArg, env: Arc<Environment>) -> Result<bool, Error> {
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![element, Value::Number(idx as f64)];
+		if predicate(&callback, "any", args, &env).await? {
+			return Ok(true);
+		}
+	}
+
+	Ok(false)
+}
+
+async fn all(array: ArrayArg, callback: CallbackArg, env: Arc<Environment>) -> Result<bool, Error> {
+	for (idx, element) in array.val.into_iter().enumerate() {
+		let args = vec![element, Value::Number(idx as f64)];
+		if !predicate(&callback, "all", args, &env).await? {
+			return Ok(false);
+		}
+	}
+
+	Ok(true)
+}
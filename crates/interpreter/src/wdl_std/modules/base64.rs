@@ -0,0 +1,60 @@
+use ::base64::{
+	Engine, alphabet,
+	engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+
+use crate::{
+	Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, get_handler, id},
+};
+
+use super::string::from_utf8;
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"encode" => Some(get_handler(encode)),
+		"decode" => Some(get_handler(decode)),
+		_ => None,
+	}
+}
+
+/// Encodes with padding, decodes with or without.
+pub const STANDARD: GeneralPurpose = GeneralPurpose::new(
+	&alphabet::STANDARD,
+	GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Encodes without padding as used in URLs and tokens, decodes with or without.
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+	&alphabet::URL_SAFE,
+	GeneralPurposeConfig::new()
+		.with_encode_padding(false)
+		.with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+type UrlArg = Option<Arg<bool, { id(b"url") }>>;
+
+fn engine(url: UrlArg) -> GeneralPurpose {
+	if url.is_some_and(|url| url.val) {
+		URL_SAFE
+	} else {
+		STANDARD
+	}
+}
+
+/// Encodes the UTF-8 bytes of the string.
+async fn encode(string: Arg<String, { id(b"string") }>, url: UrlArg) -> String {
+	engine(url).encode(string.val)
+}
+
+async fn decode(string: Arg<String, { id(b"string") }>, url: UrlArg) -> Result<String, Error> {
+	let bytes = engine(url)
+		.decode(&string.val)
+		.map_err(|err| Error::positional(format!("Invalid base64, {}", err), string.span))?;
+
+	from_utf8(bytes, string.span)
+}
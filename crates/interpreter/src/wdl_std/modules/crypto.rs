@@ -0,0 +1,129 @@
+use ::base64::Engine;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use ast::Span;
+
+use crate::{
+	Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, get_handler, id},
+};
+
+use super::base64::STANDARD;
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"sha256" => Some(get_handler(sha256)),
+		"sha1" => Some(get_handler(sha1)),
+		"hmac" => Some(get_handler(hmac)),
+		_ => None,
+	}
+}
+
+type EncodingArg = Option<Arg<String, { id(b"encoding") }>>;
+
+/// Writes the digest as `hex` (default) or `base64`.
+fn encode(digest: &[u8], encoding: EncodingArg) -> Result<String, Error> {
+	match encoding
+		.as_ref()
+		.map(|encoding| (encoding.val.as_str(), encoding.span))
+	{
+		None | Some(("hex", _)) => Ok(::hex::encode(digest)),
+		Some(("base64", _)) => Ok(STANDARD.encode(digest)),
+		Some((other, span)) => Err(Error::positional(
+			format!(
+				"Encoding `{}` is unknown, expected `hex` or `base64`",
+				other
+			),
+			span,
+		)),
+	}
+}
+
+/// Hashes the UTF-8 bytes of the string.
+async fn sha256(
+	string: Arg<String, { id(b"string") }>,
+	encoding: EncodingArg,
+) -> Result<String, Error> {
+	encode(&Sha256::digest(string.val), encoding)
+}
+
+/// Only for legacy systems, SHA-1 is not collision resistant anymore.
+async fn sha1(
+	string: Arg<String, { id(b"string") }>,
+	encoding: EncodingArg,
+) -> Result<String, Error> {
+	encode(&Sha1::digest(string.val), encoding)
+}
+
+/// Signs the message as UTF-8 bytes using SHA-256 unless `algorithm` is `sha1`.
+/// The key is given as `utf8` (default), `hex` or `base64`, so binary secrets can be used.
+async fn hmac(
+	key: Arg<String, { id(b"key") }>,
+	message: Arg<String, { id(b"message") }>,
+	algorithm: Option<Arg<String, { id(b"algorithm") }>>,
+	encoding: EncodingArg,
+	key_encoding: Option<Arg<String, { id(b"key_encoding") }>>,
+) -> Result<String, Error> {
+	let key_bytes = decode_key(&key, key_encoding)?;
+	let signature = match algorithm
+		.as_ref()
+		.map(|algorithm| (algorithm.val.as_str(), algorithm.span))
+	{
+		None | Some(("sha256", _)) => sign::<Hmac<Sha256>>(&key_bytes, &message.val, key.span)?,
+		Some(("sha1", _)) => sign::<Hmac<Sha1>>(&key_bytes, &message.val, key.span)?,
+		Some((other, span)) => {
+			return Err(Error::positional(
+				format!(
+					"Algorithm `{}` is unknown, expected `sha256` or `sha1`",
+					other
+				),
+				span,
+			));
+		}
+	};
+
+	encode(&signature, encoding)
+}
+
+fn decode_key(
+	key: &Arg<String, { id(b"key") }>,
+	key_encoding: Option<Arg<String, { id(b"key_encoding") }>>,
+) -> Result<Vec<u8>, Error> {
+	let bytes = match key_encoding
+		.as_ref()
+		.map(|key_encoding| (key_encoding.val.as_str(), key_encoding.span))
+	{
+		None | Some(("utf8", _)) => Ok(key.val.as_bytes().to_vec()),
+		Some(("hex", _)) => ::hex::decode(&key.val).map_err(|err| err.to_string()),
+		Some(("base64", _)) => STANDARD.decode(&key.val).map_err(|err| err.to_string()),
+		Some((other, span)) => {
+			return Err(Error::positional(
+				format!(
+					"Key encoding `{}` is unknown, expected `utf8`, `hex` or `base64`",
+					other
+				),
+				span,
+			));
+		}
+	};
+
+	bytes.map_err(|err| Error::positional(format!("Invalid key, {}", err), key.span))
+}
+
+fn sign<M: Mac + hmac::digest::KeyInit>(
+	key: &[u8],
+	message: &str,
+	key_span: Span,
+) -> Result<Vec<u8>, Error> {
+	let mut mac = <M as Mac>::new_from_slice(key)
+		.map_err(|err| Error::positional(format!("Invalid key, {}", err), key_span))?;
+	mac.update(message.as_bytes());
+
+	Ok(mac.finalize().into_bytes().to_vec())
+}
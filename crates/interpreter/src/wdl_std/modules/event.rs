@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use log::info;
+use serde::Serialize;
+
+use ast::Span;
+
+use crate::{
+	EVENTS, Environment, Error, FunctionId, FunctionValue, LogEntry, Value,
+	wdl_std::{Arg, ResultType, get_handler, id},
+};
+
+use super::time::millis;
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"wait" => Some(get_handler(wait)),
+		_ => None,
+	}
+}
+
+#[derive(Debug, Serialize)]
+struct Event {
+	name: String,
+	payload: Value,
+}
+
+impl ResultType for Event {}
+
+/// Returns `null` if the event is not delivered within the timeout,
+/// without timeout the workflow waits until the event is delivered.
+async fn wait(
+	name: Arg<String, { id(b"name") }>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<Option<Event>, Error> {
+	let timeout = timeout.as_ref().map(millis).transpose()?;
+	info!("wait for event `{}`", name.val);
+	env.send_log(LogEntry::info(
+		format!("Wait for event `{}`.", name.val),
+		Some(fn_span),
+	))
+	.await;
+
+	let (_subscription, ch) = EVENTS.subscribe(&name.val, &env).await;
+
+	let payload = match timeout {
+		Some(timeout) => tokio::time::timeout(timeout, ch.receive())
+			.await
+			.ok()
+			.flatten(),
+		None => ch.receive().await,
+	};
+
+	let Some(payload) = payload else {
+		env.send_log(LogEntry::info(
+			format!("Event `{}` not received in time.", name.val),
+			Some(fn_span),
+		))
+		.await;
+		return Ok(None);
+	};
+
+	env.send_log(LogEntry::info(
+		format!("Event `{}` received.", name.val),
+		Some(fn_span),
+	))
+	.await;
+
+	Ok(Some(Event {
+		name: name.val,
+		payload,
+	}))
+}
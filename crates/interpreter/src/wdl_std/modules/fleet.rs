@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use log::info;
+
+use ast::Span;
+use router::{RouterClient, Vehicle, VehicleSelector};
+
+use crate::{
+	Environment, Error, ErrorKind, FunctionId, FunctionValue, LogEntry,
+	wdl_std::{Arg, ResultType, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"reserve" => Some(get_handler(reserve)),
+		"release" => Some(get_handler(release)),
+		_ => None,
+	}
+}
+
+impl ResultType for Vehicle {}
+
+/// Vehicles not released by the workflow are released when it stops.
+async fn reserve(
+	vehicle: Option<Arg<VehicleSelector, { id(b"vehicle") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<Vehicle, Error> {
+	let selector = vehicle.map(|vehicle| vehicle.val).unwrap_or_default();
+	info!("reserve vehicle {:?}", selector);
+
+	let vehicle = env.router.reserve(selector).await.map_err(|err| Error {
+		kind: ErrorKind::Router(err),
+		span: Some(fn_span),
+	})?;
+	env.reserve_vehicle(vehicle.id.clone()).await;
+
+	env.send_log(LogEntry::info(
+		format!("Vehicle `{}` reserved.", vehicle.id),
+		Some(fn_span),
+	))
+	.await;
+
+	Ok(vehicle)
+}
+
+async fn release(
+	vehicle: Arg<Vehicle, { id(b"vehicle") }>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<(), Error> {
+	info!("release vehicle `{}`", vehicle.val.id);
+
+	env.router
+		.release(&vehicle.val.id)
+		.await
+		.map_err(|err| Error {
+			kind: ErrorKind::Router(err),
+			span: Some(fn_span),
+		})?;
+	env.release_vehicle(&vehicle.val.id).await;
+
+	env.send_log(LogEntry::info(
+		format!("Vehicle `{}` released.", vehicle.val.id),
+		Some(fn_span),
+	))
+	.await;
+
+	Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{
+	Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, get_handler, id},
+};
+
+use super::string::from_utf8;
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"encode" => Some(get_handler(encode)),
+		"decode" => Some(get_handler(decode)),
+		_ => None,
+	}
+}
+
+/// Encodes the UTF-8 bytes of the string with lowercase digits.
+async fn encode(string: Arg<String, { id(b"string") }>) -> String {
+	::hex::encode(string.val)
+}
+
+/// Accepts lowercase and uppercase digits.
+async fn decode(string: Arg<String, { id(b"string") }>) -> Result<String, Error> {
+	let bytes = ::hex::decode(&string.val)
+		.map_err(|err| Error::positional(format!("Invalid hex, {}", err), string.span))?;
+
+	from_utf8(bytes, string.span)
+}
@@ -1,91 +1,376 @@
 use std::{collections::HashMap, sync::Arc};
 
 use log::error;
-use reqwest::{Response, Url, header::CONTENT_TYPE};
-use serde::Serialize;
+use reqwest::{Method, RequestBuilder, Response, Url, header::CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
 
 use ast::Span;
 
 use crate::{
 	Environment, Error, ErrorKind, FunctionId, FunctionValue, LogEntry, Value,
-	wdl_std::{Arg, ResultType, get_handler, id},
+	wdl_std::{Arg, ArgType, ResultType, get_handler, id},
 };
 
+use super::{json::to_json, time::millis};
+
 pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 	if id.scope.len() > 1 {
 		return None;
 	}
 
 	match id.id.id.as_str() {
+		"request" => Some(get_handler(request)),
 		"get" => Some(get_handler(get)),
 		"post" => Some(get_handler(post)),
+		"put" => Some(get_handler(put)),
+		"patch" => Some(get_handler(patch)),
+		"delete" => Some(get_handler(delete)),
 		_ => None,
 	}
 }
 
 #[derive(Debug, Serialize)]
 struct HttpResponse {
-	status: u16,
+	status: Option<u16>,
 	headers: HashMap<String, String>,
 	body: Value,
+	error: Option<HttpError>,
 }
 
 impl ResultType for HttpResponse {}
 
-async fn get(
-	url: Arg<String, { id(b"url") }>,
+/// Reason why no response or no readable response was received.
+/// `kind` is one of `timeout`, `connect`, `redirect`, `body`, `decode` or `request`.
+#[derive(Debug, Serialize)]
+struct HttpError {
+	kind: &'static str,
+	message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum HttpAuth {
+	Bearer {
+		bearer: String,
+	},
+	Basic {
+		username: String,
+		password: Option<String>,
+	},
+}
+
+impl ArgType<'_> for HttpAuth {}
+
+type UrlArg = Arg<String, { id(b"url") }>;
+type HeadersArg = Option<Arg<HashMap<String, Value>, { id(b"headers") }>>;
+type QueryArg = Option<Arg<HashMap<String, Value>, { id(b"query") }>>;
+type BodyArg = Option<Arg<Value, { id(b"body") }>>;
+type ContentArg = Option<Arg<String, { id(b"content") }>>;
+type TimeoutArg = Option<Arg<f64, { id(b"timeout") }>>;
+type AuthArg = Option<Arg<HttpAuth, { id(b"auth") }>>;
+
+/// Optional parts of a request shared by all functions of the module.
+#[derive(Default)]
+struct RequestOptions {
+	headers: HeadersArg,
+	query: QueryArg,
+	body: BodyArg,
+	content: ContentArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
+}
+
+/// Sends the request with any method,
+/// failed requests are answered with the `error` member set.
+#[allow(clippy::too_many_arguments)]
+async fn request(
+	method: Arg<String, { id(b"method") }>,
+	url: UrlArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	body: BodyArg,
+	content: ContentArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
 	fn_span: Span,
 	env: Arc<Environment>,
-) -> Result<Option<HttpResponse>, Error> {
-	env.send_log(LogEntry::info(
-		format!("Send GET request to `{}`.", url.val),
-		Some(fn_span),
-	))
-	.await;
+) -> Result<HttpResponse, Error> {
+	let Ok(method_val) = Method::from_bytes(method.val.to_uppercase().as_bytes()) else {
+		return Err(Error::positional(
+			format!("Invalid HTTP method `{}`", method.val),
+			method.span,
+		));
+	};
 
-	let ret = process_response(reqwest::get(parse_url(&url.val, url.span)?).await).await?;
+	let options = RequestOptions {
+		headers,
+		query,
+		body,
+		content,
+		timeout,
+		auth,
+	};
+	send(method_val, url, options, fn_span, &env).await
+}
 
-	env.send_log(LogEntry::info(
-		format!(
-			"Response: {}.",
-			serde_json::to_string(&ret).unwrap_or("<internal error>".to_owned())
-		),
-		Some(fn_span),
-	))
-	.await;
+async fn get(
+	url: UrlArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<HttpResponse, Error> {
+	let options = RequestOptions {
+		headers,
+		query,
+		timeout,
+		auth,
+		..Default::default()
+	};
 
-	Ok(ret)
+	send(Method::GET, url, options, fn_span, &env).await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn post(
-	url: Arg<String, { id(b"url") }>,
+	url: UrlArg,
+	body: BodyArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	content: ContentArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
 	fn_span: Span,
 	env: Arc<Environment>,
-) -> Result<Option<HttpResponse>, Error> {
+) -> Result<HttpResponse, Error> {
+	let options = RequestOptions {
+		headers,
+		query,
+		body,
+		content,
+		timeout,
+		auth,
+	};
+
+	send(Method::POST, url, options, fn_span, &env).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn put(
+	url: UrlArg,
+	body: BodyArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	content: ContentArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<HttpResponse, Error> {
+	let options = RequestOptions {
+		headers,
+		query,
+		body,
+		content,
+		timeout,
+		auth,
+	};
+
+	send(Method::PUT, url, options, fn_span, &env).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn patch(
+	url: UrlArg,
+	body: BodyArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	content: ContentArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<HttpResponse, Error> {
+	let options = RequestOptions {
+		headers,
+		query,
+		body,
+		content,
+		timeout,
+		auth,
+	};
+
+	send(Method::PATCH, url, options, fn_span, &env).await
+}
+
+async fn delete(
+	url: UrlArg,
+	headers: HeadersArg,
+	query: QueryArg,
+	timeout: TimeoutArg,
+	auth: AuthArg,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<HttpResponse, Error> {
+	let options = RequestOptions {
+		headers,
+		query,
+		timeout,
+		auth,
+		..Default::default()
+	};
+
+	send(Method::DELETE, url, options, fn_span, &env).await
+}
+
+/// Invalid arguments stop the workflow, problems while sending
+/// the request are answered with the `error` member set.
+async fn send(
+	method: Method,
+	url: UrlArg,
+	options: RequestOptions,
+	fn_span: Span,
+	env: &Arc<Environment>,
+) -> Result<HttpResponse, Error> {
+	let builder = build_request(method.clone(), &url, options)?;
+
 	env.send_log(LogEntry::info(
-		format!("Send POST request to `{}`.", url.val),
+		format!("Send {} request to `{}`.", method, url.val),
 		Some(fn_span),
 	))
 	.await;
 
-	let ret = process_response(
-		reqwest::Client::new()
-			.post(parse_url(&url.val, url.span)?)
-			.send()
-			.await,
-	)
-	.await?;
+	let ret = process_response(builder.send().await).await;
 
-	env.send_log(LogEntry::info(
-		format!(
+	let msg = match &ret {
+		Ok(response) => format!(
 			"Response: {}.",
-			serde_json::to_string(&ret).unwrap_or("<internal error>".to_owned())
+			serde_json::to_string(response).unwrap_or("<internal error>".to_owned())
 		),
-		Some(fn_span),
-	))
-	.await;
+		Err(err) => format!("Request failed, {}: {}.", err.kind, err.message),
+	};
+	env.send_log(LogEntry::info(msg, Some(fn_span))).await;
 
-	Ok(ret)
+	Ok(ret.unwrap_or_else(|error| HttpResponse {
+		status: None,
+		headers: HashMap::new(),
+		body: Value::Null,
+		error: Some(error),
+	}))
+}
+
+fn build_request(
+	method: Method,
+	url: &UrlArg,
+	options: RequestOptions,
+) -> Result<RequestBuilder, Error> {
+	let mut builder = reqwest::Client::new().request(method, parse_url(&url.val, url.span)?);
+
+	if let Some(headers) = options.headers {
+		for (name, value) in headers.val {
+			let value = scalar(&value, &name, headers.span)?;
+			if reqwest::header::HeaderName::try_from(name.as_str()).is_err()
+				|| reqwest::header::HeaderValue::try_from(value.as_str()).is_err()
+			{
+				return Err(Error::positional(
+					format!("Invalid header `{}`", name),
+					headers.span,
+				));
+			}
+			builder = builder.header(name, value);
+		}
+	}
+
+	if let Some(query) = options.query {
+		let mut params = Vec::new();
+		for (name, value) in &query.val {
+			params.push((name.clone(), scalar(value, name, query.span)?));
+		}
+		builder = builder.query(&params);
+	}
+
+	if let Some(body) = options.body {
+		builder = match (content_type(&body, &options.content)?, body.val) {
+			("json", value) => builder
+				.header(CONTENT_TYPE, "application/json")
+				.body(to_json(value, body.span)?.to_string()),
+			("form", Value::Object(fields)) => {
+				let mut form = Vec::new();
+				for (name, value) in &fields {
+					form.push((name.clone(), scalar(value, name, body.span)?));
+				}
+				builder.form(&form)
+			}
+			("text", Value::String(text)) => builder
+				.header(CONTENT_TYPE, "text/plain; charset=utf-8")
+				.body(text),
+			(content, value) => {
+				return Err(Error {
+					kind: ErrorKind::InvalidType {
+						msg: format!("`{}` body given as `{}`", content, value.get_type()),
+					},
+					span: Some(body.span),
+				});
+			}
+		};
+	}
+
+	if let Some(timeout) = options.timeout {
+		builder = builder.timeout(millis(&timeout)?);
+	}
+
+	builder = match options.auth.map(|auth| auth.val) {
+		Some(HttpAuth::Bearer { bearer }) => builder.bearer_auth(bearer),
+		Some(HttpAuth::Basic { username, password }) => builder.basic_auth(username, password),
+		None => builder,
+	};
+
+	Ok(builder)
+}
+
+/// Strings are sent as text and all other values as JSON, unless `content` says otherwise.
+fn content_type(
+	body: &Arg<Value, { id(b"body") }>,
+	content: &ContentArg,
+) -> Result<&'static str, Error> {
+	let Some(content) = content else {
+		return Ok(match body.val {
+			Value::String(_) => "text",
+			_ => "json",
+		});
+	};
+
+	match content.val.as_str() {
+		"json" => Ok("json"),
+		"form" => Ok("form"),
+		"text" => Ok("text"),
+		other => Err(Error::positional(
+			format!(
+				"Content `{}` is unknown, expected `json`, `form` or `text`",
+				other
+			),
+			content.span,
+		)),
+	}
+}
+
+/// Headers, query parameters and form fields only take strings, numbers and bools.
+fn scalar(value: &Value, name: &str, span: Span) -> Result<String, Error> {
+	match value {
+		Value::String(_) | Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+		other => Err(Error {
+			kind: ErrorKind::InvalidType {
+				msg: format!(
+					"`{}` given for `{}`, expected string, number or bool",
+					other.get_type(),
+					name
+				),
+			},
+			span: Some(span),
+		}),
+	}
 }
 
 fn parse_url(url: &str, src: Span) -> Result<Url, Error> {
@@ -121,17 +406,34 @@ fn parse_url(url: &str, src: Span) -> Result<Url, Error> {
 	Ok(url)
 }
 
-async fn process_response(
-	response: reqwest::Result<Response>,
-) -> Result<Option<HttpResponse>, Error> {
-	let response = match response {
-		Ok(r) => r,
-		Err(err) => {
-			// TODO: return error on builder error
-			error!("{}", err.to_string());
-			return Ok(None);
+impl From<reqwest::Error> for HttpError {
+	fn from(err: reqwest::Error) -> Self {
+		let kind = if err.is_timeout() {
+			"timeout"
+		} else if err.is_connect() {
+			"connect"
+		} else if err.is_redirect() {
+			"redirect"
+		} else if err.is_body() {
+			"body"
+		} else if err.is_decode() {
+			"decode"
+		} else {
+			"request"
+		};
+
+		HttpError {
+			kind,
+			message: err.to_string(),
 		}
-	};
+	}
+}
+
+async fn process_response(response: reqwest::Result<Response>) -> Result<HttpResponse, HttpError> {
+	let response = response.map_err(|err| {
+		error!("{}", err);
+		HttpError::from(err)
+	})?;
 
 	let mut headers = HashMap::new();
 	for (h_n, h_v) in response.headers() {
@@ -144,9 +446,10 @@ async fn process_response(
 	}
 
 	let mut res = HttpResponse {
-		status: response.status().as_u16(),
+		status: Some(response.status().as_u16()),
 		headers,
 		body: Value::Null,
+		error: None,
 	};
 
 	let mut json = false;
@@ -161,7 +464,8 @@ async fn process_response(
 		Ok(b) => b,
 		Err(err) => {
 			error!("Failed to read response body `{}`", err);
-			return Ok(Some(res));
+			res.error = Some(HttpError::from(err));
+			return Ok(res);
 		}
 	};
 
@@ -170,11 +474,17 @@ async fn process_response(
 			Ok(val) => res.body = val,
 			Err(err) => {
 				error!("Failed to json decode body `{}`", err);
+				// the raw body is kept, so the workflow can still inspect it
+				res.body = Value::String(body);
+				res.error = Some(HttpError {
+					kind: "decode",
+					message: err.to_string(),
+				});
 			}
 		};
 	} else {
 		res.body = Value::String(body);
 	}
 
-	Ok(Some(res))
+	Ok(res)
 }
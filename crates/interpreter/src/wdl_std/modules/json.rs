@@ -0,0 +1,100 @@
+use ast::Span;
+
+use crate::{
+	Error, FunctionId, FunctionValue, Value,
+	wdl_std::{Arg, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"parse" => Some(get_handler(parse)),
+		"stringify" => Some(get_handler(stringify)),
+		_ => None,
+	}
+}
+
+/// Objects are always parsed as objects, even if they look like a function or channel.
+async fn parse(string: Arg<String, { id(b"string") }>) -> Result<Value, Error> {
+	let json: serde_json::Value = serde_json::from_str(&string.val)
+		.map_err(|err| Error::positional(format!("Invalid JSON, {}", err), string.span))?;
+
+	Ok(from_json(json))
+}
+
+/// Object members are written sorted by key.
+async fn stringify(
+	value: Arg<Value, { id(b"value") }>,
+	pretty: Option<Arg<bool, { id(b"pretty") }>>,
+) -> Result<String, Error> {
+	let json = to_json(value.val, value.span)?;
+
+	let string = if pretty.is_some_and(|pretty| pretty.val) {
+		serde_json::to_string_pretty(&json)
+	} else {
+		serde_json::to_string(&json)
+	};
+
+	string.map_err(|err| Error::positional(err.to_string(), value.span))
+}
+
+pub fn from_json(json: serde_json::Value) -> Value {
+	match json {
+		serde_json::Value::Null => Value::Null,
+		serde_json::Value::Bool(val) => Value::Bool(val),
+		serde_json::Value::Number(val) => Value::Number(val.as_f64().unwrap_or(f64::NAN)),
+		serde_json::Value::String(val) => Value::String(val),
+		serde_json::Value::Array(val) => Value::Array(val.into_iter().map(from_json).collect()),
+		serde_json::Value::Object(val) => Value::Object(
+			val.into_iter()
+				.map(|(key, val)| (key, from_json(val)))
+				.collect(),
+		),
+	}
+}
+
+/// Functions, channels and numbers which are not finite have no JSON representation.
+pub fn to_json(value: Value, span: Span) -> Result<serde_json::Value, Error> {
+	let json = match value {
+		Value::Null => serde_json::Value::Null,
+		Value::Bool(val) => serde_json::Value::Bool(val),
+		Value::Number(val) => match serde_json::Number::from_f64(val) {
+			// whole numbers are written without fraction
+			Some(_) if val.fract() == 0.0 && val.abs() < 2f64.powi(53) => {
+				serde_json::Value::from(val as i64)
+			}
+			Some(number) => serde_json::Value::Number(number),
+			None => {
+				return Err(Error::positional(
+					format!("Number `{}` cannot be written as JSON", val),
+					span,
+				));
+			}
+		},
+		Value::String(val) => serde_json::Value::String(val),
+		Value::Array(val) => serde_json::Value::Array(
+			val.into_iter()
+				.map(|val| to_json(val, span))
+				.collect::<Result<_, _>>()?,
+		),
+		Value::Object(val) => serde_json::Value::Object(
+			val.into_iter()
+				.map(|(key, val)| Ok((key, to_json(val, span)?)))
+				.collect::<Result<_, Error>>()?,
+		),
+		other @ (Value::Function(_) | Value::Channel(_)) => {
+			return Err(Error::positional(
+				format!(
+					"Value of type `{}` cannot be written as JSON",
+					other.get_type()
+				),
+				span,
+			));
+		}
+	};
+
+	Ok(json)
+}
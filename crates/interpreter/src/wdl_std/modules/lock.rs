@@ -0,0 +1,99 @@
+use std::{sync::Arc, time::Duration};
+
+use log::info;
+
+use ast::Span;
+
+use crate::{
+	Environment, Error, FunctionId, FunctionValue, LOCKS, LockError, LogEntry,
+	wdl_std::{Arg, get_handler, id},
+};
+
+use super::time::millis;
+
+/// Time to wait for a lock if no timeout is given,
+/// waiting longer most likely means the workflows are deadlocked.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"acquire" => Some(get_handler(acquire)),
+		"release" => Some(get_handler(release)),
+		_ => None,
+	}
+}
+
+/// Locks with a `limit` greater than one are counted semaphores.
+async fn acquire(
+	name: Arg<String, { id(b"name") }>,
+	timeout: Option<Arg<f64, { id(b"timeout") }>>,
+	limit: Option<Arg<f64, { id(b"limit") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<(), Error> {
+	let limit_val = match &limit {
+		Some(limit) if limit.val < 1.0 || limit.val.fract() != 0.0 => {
+			return Err(Error::positional(
+				"Lock limit must be a positive integer",
+				limit.span,
+			));
+		}
+		Some(limit) => limit.val as u32,
+		None => 1,
+	};
+	let timeout = match &timeout {
+		Some(timeout) => millis(timeout)?,
+		None => DEFAULT_TIMEOUT,
+	};
+	info!("acquire lock `{}`", name.val);
+
+	let permit = LOCKS
+		.acquire(&name.val, limit_val, timeout)
+		.await
+		.map_err(|err| match err {
+			LockError::LimitMismatch { limit: expected } => Error::positional(
+				format!("Lock `{}` is used with a limit of {}", name.val, expected),
+				limit.as_ref().map(|limit| limit.span).unwrap_or(fn_span),
+			),
+			LockError::Timeout => Error::positional(
+				format!(
+					"Lock `{}` not acquired within {} ms, possible deadlock",
+					name.val,
+					timeout.as_millis()
+				),
+				fn_span,
+			),
+		})?;
+	env.hold_lock(name.val.clone(), permit).await;
+
+	env.send_log(LogEntry::info(
+		format!("Lock `{}` acquired.", name.val),
+		Some(fn_span),
+	))
+	.await;
+
+	Ok(())
+}
+
+async fn release(name: Arg<String, { id(b"name") }>, env: Arc<Environment>) -> Result<(), Error> {
+	info!("release lock `{}`", name.val);
+
+	if !env.release_lock(&name.val).await {
+		return Err(Error::positional(
+			format!("Lock `{}` is not held by the workflow", name.val),
+			name.span,
+		));
+	}
+
+	env.send_log(LogEntry::info(
+		format!("Lock `{}` released.", name.val),
+		Some(name.span),
+	))
+	.await;
+
+	Ok(())
+}
@@ -0,0 +1,184 @@
+use std::f64::consts::PI;
+
+use ast::Span;
+use router::Coordinate;
+
+use crate::{
+	Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"floor" => Some(get_handler(floor)),
+		"ceil" => Some(get_handler(ceil)),
+		"round" => Some(get_handler(round)),
+		"abs" => Some(get_handler(abs)),
+		"min" => Some(get_handler(min)),
+		"max" => Some(get_handler(max)),
+		"clamp" => Some(get_handler(clamp)),
+		"pow" => Some(get_handler(pow)),
+		"sqrt" => Some(get_handler(sqrt)),
+		"sin" => Some(get_handler(sin)),
+		"cos" => Some(get_handler(cos)),
+		"tan" => Some(get_handler(tan)),
+		"asin" => Some(get_handler(asin)),
+		"acos" => Some(get_handler(acos)),
+		"atan" => Some(get_handler(atan)),
+		"atan2" => Some(get_handler(atan2)),
+		"pi" => Some(get_handler(pi)),
+		"is_nan" => Some(get_handler(is_nan)),
+		"distance" => Some(get_handler(distance)),
+		_ => None,
+	}
+}
+
+/// Fails if the argument is NaN, operations on NaN would only spread it.
+fn number<const N: u32>(arg: &Arg<f64, N>) -> Result<f64, Error> {
+	if arg.val.is_nan() {
+		return Err(Error::positional("Argument is not a number", arg.span));
+	}
+
+	Ok(arg.val)
+}
+
+/// Fails if the result is NaN or infinite, which means the arguments are outside of the domain.
+fn finite(val: f64, msg: &str, span: Span) -> Result<f64, Error> {
+	if !val.is_finite() {
+		return Err(Error::positional(msg, span));
+	}
+
+	Ok(val)
+}
+
+async fn floor(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.floor())
+}
+
+async fn ceil(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.ceil())
+}
+
+/// Rounds half-way cases away from zero.
+async fn round(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.round())
+}
+
+async fn abs(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.abs())
+}
+
+async fn min(x: Arg<f64, { id(b"x") }>, y: Arg<f64, { id(b"y") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.min(number(&y)?))
+}
+
+async fn max(x: Arg<f64, { id(b"x") }>, y: Arg<f64, { id(b"y") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.max(number(&y)?))
+}
+
+async fn clamp(
+	x: Arg<f64, { id(b"x") }>,
+	min: Arg<f64, { id(b"min") }>,
+	max: Arg<f64, { id(b"max") }>,
+	fn_span: Span,
+) -> Result<f64, Error> {
+	let (min_val, max_val) = (number(&min)?, number(&max)?);
+	if min_val > max_val {
+		return Err(Error::positional(
+			format!(
+				"Lower bound {} is greater than upper bound {}",
+				min_val, max_val
+			),
+			fn_span,
+		));
+	}
+
+	Ok(number(&x)?.clamp(min_val, max_val))
+}
+
+async fn pow(
+	x: Arg<f64, { id(b"x") }>,
+	y: Arg<f64, { id(b"y") }>,
+	fn_span: Span,
+) -> Result<f64, Error> {
+	finite(
+		number(&x)?.powf(number(&y)?),
+		"Power is not a finite number",
+		fn_span,
+	)
+}
+
+async fn sqrt(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	let val = number(&x)?;
+	if val < 0.0 {
+		return Err(Error::positional(
+			"Square root of a negative number",
+			x.span,
+		));
+	}
+
+	Ok(val.sqrt())
+}
+
+/// Angles are given in radians.
+async fn sin(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	finite(number(&x)?.sin(), "Sine of an infinite angle", x.span)
+}
+
+async fn cos(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	finite(number(&x)?.cos(), "Cosine of an infinite angle", x.span)
+}
+
+async fn tan(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	finite(number(&x)?.tan(), "Tangent of an infinite angle", x.span)
+}
+
+async fn asin(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	finite(
+		number(&x)?.asin(),
+		"Arcsine is only defined between -1 and 1",
+		x.span,
+	)
+}
+
+async fn acos(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	finite(
+		number(&x)?.acos(),
+		"Arccosine is only defined between -1 and 1",
+		x.span,
+	)
+}
+
+async fn atan(x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&x)?.atan())
+}
+
+/// Angle of the point `(x, y)`, following the argument order of `atan2(y, x)`.
+async fn atan2(y: Arg<f64, { id(b"y") }>, x: Arg<f64, { id(b"x") }>) -> Result<f64, Error> {
+	Ok(number(&y)?.atan2(number(&x)?))
+}
+
+async fn pi() -> f64 {
+	PI
+}
+
+async fn is_nan(x: Arg<f64, { id(b"x") }>) -> bool {
+	x.val.is_nan()
+}
+
+/// Straight line distance between both coordinates.
+async fn distance(
+	from: Arg<Coordinate, { id(b"from") }>,
+	to: Arg<Coordinate, { id(b"to") }>,
+	fn_span: Span,
+) -> Result<f64, Error> {
+	finite(
+		(from.val.x - to.val.x).hypot(from.val.y - to.val.y),
+		"Distance is not a finite number",
+		fn_span,
+	)
+}
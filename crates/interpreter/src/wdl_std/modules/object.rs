@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::{
+	FunctionId, FunctionValue, Value,
+	wdl_std::{Arg, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"keys" => Some(get_handler(keys)),
+		"values" => Some(get_handler(values)),
+		"entries" => Some(get_handler(entries)),
+		"has" => Some(get_handler(has)),
+		"remove" => Some(get_handler(remove)),
+		"merge" => Some(get_handler(merge)),
+		_ => None,
+	}
+}
+
+type ObjectArg = Arg<HashMap<String, Value>, { id(b"object") }>;
+
+/// Members sorted by key, objects do not keep the order of their members.
+fn sorted(object: HashMap<String, Value>) -> Vec<(String, Value)> {
+	let mut members: Vec<_> = object.into_iter().collect();
+	members.sort_by(|(a, _), (b, _)| a.cmp(b));
+	members
+}
+
+async fn keys(object: ObjectArg) -> Vec<String> {
+	sorted(object.val).into_iter().map(|(key, _)| key).collect()
+}
+
+async fn values(object: ObjectArg) -> Vec<Value> {
+	sorted(object.val)
+		.into_iter()
+		.map(|(_, value)| value)
+		.collect()
+}
+
+/// Returns objects with the members `key` and `value`.
+async fn entries(object: ObjectArg) -> Vec<HashMap<String, Value>> {
+	sorted(object.val)
+		.into_iter()
+		.map(|(key, value)| {
+			HashMap::from([
+				("key".to_owned(), Value::String(key)),
+				("value".to_owned(), value),
+			])
+		})
+		.collect()
+}
+
+async fn has(object: ObjectArg, key: Arg<String, { id(b"key") }>) -> bool {
+	object.val.contains_key(&key.val)
+}
+
+/// Objects are values, so the changed object is returned.
+async fn remove(object: ObjectArg, key: Arg<String, { id(b"key") }>) -> HashMap<String, Value> {
+	let mut object = object.val;
+	object.remove(&key.val);
+	object
+}
+
+/// Members of `other` replace the ones of `object`,
+/// nested objects are merged as well if `deep` is set.
+async fn merge(
+	object: ObjectArg,
+	other: Arg<HashMap<String, Value>, { id(b"other") }>,
+	deep: Option<Arg<bool, { id(b"deep") }>>,
+) -> HashMap<String, Value> {
+	let deep = deep.is_some_and(|deep| deep.val);
+
+	merge_objects(object.val, other.val, deep)
+}
+
+fn merge_objects(
+	mut object: HashMap<String, Value>,
+	other: HashMap<String, Value>,
+	deep: bool,
+) -> HashMap<String, Value> {
+	for (key, value) in other {
+		let merged = match (object.remove(&key), value) {
+			(Some(Value::Object(inner)), Value::Object(other_inner)) if deep => {
+				Value::Object(merge_objects(inner, other_inner, deep))
+			}
+			(_, value) => value,
+		};
+		object.insert(key, merged);
+	}
+
+	object
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+	Environment, Error, FunctionId, FunctionValue, Value,
+	wdl_std::{Arg, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"number" => Some(get_handler(number)),
+		"int" => Some(get_handler(int)),
+		"choice" => Some(get_handler(choice)),
+		"shuffle" => Some(get_handler(shuffle)),
+		_ => None,
+	}
+}
+
+type ArrayArg = Arg<Vec<Value>, { id(b"array") }>;
+
+/// Integers up to this size are represented exactly by numbers.
+const MAX_SAFE_INT: f64 = 9_007_199_254_740_991.0;
+
+/// Returns a number between `min` inclusive and `max` exclusive, between 0 and 1 by default.
+async fn number(
+	min: Option<Arg<f64, { id(b"min") }>>,
+	max: Option<Arg<f64, { id(b"max") }>>,
+	env: Arc<Environment>,
+) -> Result<f64, Error> {
+	let min = min.map(|min| (min.val, min.span));
+	let max = max.map(|max| (max.val, max.span));
+	for (val, span) in [min, max].into_iter().flatten() {
+		if !val.is_finite() {
+			return Err(Error::positional(
+				format!("Expected a finite number, but `{}` given", val),
+				span,
+			));
+		}
+	}
+
+	let lower = min.map_or(0.0, |(min, _)| min);
+	let upper = max.map_or(1.0, |(max, _)| max);
+	if lower > upper || !(upper - lower).is_finite() {
+		let span = max.or(min).map(|(_, span)| span).unwrap_or_default();
+		return Err(Error::positional(
+			format!("Invalid range from `{}` to `{}`", lower, upper),
+			span,
+		));
+	}
+	if lower == upper {
+		return Ok(lower);
+	}
+
+	Ok(env.random(|rng| rng.gen_range(lower..upper)))
+}
+
+/// Returns a whole number between `min` and `max`, both inclusive.
+async fn int(
+	min: Arg<f64, { id(b"min") }>,
+	max: Arg<f64, { id(b"max") }>,
+	env: Arc<Environment>,
+) -> Result<f64, Error> {
+	for (val, span) in [(min.val, min.span), (max.val, max.span)] {
+		if val.fract() != 0.0 || val.abs() > MAX_SAFE_INT {
+			return Err(Error::positional(
+				format!("Expected a whole number, but `{}` given", val),
+				span,
+			));
+		}
+	}
+	if min.val > max.val {
+		return Err(Error::positional(
+			format!("Invalid range from `{}` to `{}`", min.val, max.val),
+			max.span,
+		));
+	}
+
+	let (min, max) = (min.val as i64, max.val as i64);
+
+	Ok(env.random(|rng| rng.gen_range(min..=max)) as f64)
+}
+
+/// Returns `null` for an empty array.
+async fn choice(array: ArrayArg, env: Arc<Environment>) -> Option<Value> {
+	env.random(|rng| array.val.choose(rng).cloned())
+}
+
+/// Arrays are values, so a shuffled copy is returned.
+async fn shuffle(array: ArrayArg, env: Arc<Environment>) -> Vec<Value> {
+	let mut array = array.val;
+	env.random(|rng| array.shuffle(rng));
+	array
+}
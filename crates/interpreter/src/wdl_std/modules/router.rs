@@ -0,0 +1,52 @@
+use std::{sync::Arc, time::Duration};
+
+use log::info;
+
+use ast::Span;
+use router::{RouterCapabilities, RouterClient, RouterError};
+
+use crate::{
+	Environment, Error, ErrorKind, FunctionId, FunctionValue,
+	wdl_std::{ResultType, get_handler},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"available" => Some(get_handler(available)),
+		"capabilities" => Some(get_handler(capabilities)),
+		_ => None,
+	}
+}
+
+/// Routers which do not answer the health check in time are unavailable.
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(3);
+
+impl ResultType for RouterCapabilities {}
+
+/// Unreachable routers are reported as unavailable instead of failing the workflow.
+async fn available(env: Arc<Environment>) -> Result<bool, Error> {
+	let health = tokio::time::timeout(HEALTH_TIMEOUT, env.router.health())
+		.await
+		.unwrap_or(Err(RouterError::Timeout));
+
+	let available = match health {
+		Ok(health) => health.ready,
+		Err(err) => {
+			info!("router health check failed: {}", err);
+			false
+		}
+	};
+
+	Ok(available)
+}
+
+async fn capabilities(fn_span: Span, env: Arc<Environment>) -> Result<RouterCapabilities, Error> {
+	env.router.capabilities().await.map_err(|err| Error {
+		kind: ErrorKind::Router(err),
+		span: Some(fn_span),
+	})
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use ::site::Site;
+use ast::Span;
+use router::Coordinate;
+
+use crate::{
+	Environment, Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, ArgType, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"stations" => Some(get_handler(stations)),
+		"distance" => Some(get_handler(distance)),
+		"nearest" => Some(get_handler(nearest)),
+		_ => None,
+	}
+}
+
+/// Either the name of a station or a coordinate.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Location {
+	Station(String),
+	Coordinate(Coordinate),
+}
+
+impl ArgType<'_> for Location {}
+
+fn get_site(env: &Environment, fn_span: Span) -> Result<&Site, Error> {
+	env.site.as_ref().ok_or_else(|| {
+		Error::positional(
+			"No site map loaded, the workflow must be run with a site file",
+			fn_span,
+		)
+	})
+}
+
+fn check_area(site: &Site, area: &Arg<String, { id(b"area") }>) -> Result<(), Error> {
+	if site.stationarea(&area.val).is_none() {
+		return Err(Error::positional(
+			format!("Station area `{}` is unknown", area.val),
+			area.span,
+		));
+	}
+
+	Ok(())
+}
+
+async fn stations(
+	area: Option<Arg<String, { id(b"area") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<Vec<String>, Error> {
+	let site = get_site(&env, fn_span)?;
+	if let Some(area) = &area {
+		check_area(site, area)?;
+	}
+
+	let stations = site
+		.stations_in(area.as_ref().map(|area| area.val.as_str()))
+		.unwrap_or_default();
+
+	Ok(stations
+		.into_iter()
+		.map(|station| station.name.clone())
+		.collect())
+}
+
+/// Length of the shortest route along the paths of the site.
+async fn distance(
+	from: Arg<String, { id(b"from") }>,
+	to: Arg<String, { id(b"to") }>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<f64, Error> {
+	let site = get_site(&env, fn_span)?;
+	for (station, span) in [(&from.val, from.span), (&to.val, to.span)] {
+		if site.station(station).is_none() {
+			return Err(Error::positional(
+				format!("Station `{}` is unknown", station),
+				span,
+			));
+		}
+	}
+
+	site.distance(&from.val, &to.val).ok_or_else(|| {
+		Error::positional(
+			format!("No route from `{}` to `{}`", from.val, to.val),
+			fn_span,
+		)
+	})
+}
+
+async fn nearest(
+	to: Arg<Location, { id(b"to") }>,
+	area: Option<Arg<String, { id(b"area") }>>,
+	fn_span: Span,
+	env: Arc<Environment>,
+) -> Result<String, Error> {
+	let site = get_site(&env, fn_span)?;
+	let (x, y) = match &to.val {
+		Location::Station(name) => {
+			let Some(station) = site.station(name) else {
+				return Err(Error::positional(
+					format!("Station `{}` is unknown", name),
+					to.span,
+				));
+			};
+			(station.x, station.y)
+		}
+		Location::Coordinate(coordinate) => (coordinate.x, coordinate.y),
+	};
+	if let Some(area) = &area {
+		check_area(site, area)?;
+	}
+
+	let nearest = site.nearest(x, y, area.as_ref().map(|area| area.val.as_str()));
+	nearest
+		.map(|station| station.name.clone())
+		.ok_or_else(|| Error::positional("The site has no stations", fn_span))
+}
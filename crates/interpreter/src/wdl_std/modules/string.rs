@@ -0,0 +1,233 @@
+use ast::Span;
+
+use crate::{
+	Error, ErrorKind, FunctionId, FunctionValue, Value,
+	wdl_std::{Arg, get_handler, id},
+};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"len" => Some(get_handler(len)),
+		"split" => Some(get_handler(split)),
+		"join" => Some(get_handler(join)),
+		"trim" => Some(get_handler(trim)),
+		"upper" => Some(get_handler(upper)),
+		"lower" => Some(get_handler(lower)),
+		"starts_with" => Some(get_handler(starts_with)),
+		"ends_with" => Some(get_handler(ends_with)),
+		"contains" => Some(get_handler(contains)),
+		"substring" => Some(get_handler(substring)),
+		"pad" => Some(get_handler(pad)),
+		"parse_number" => Some(get_handler(parse_number)),
+		"to_string" => Some(get_handler(to_string)),
+		_ => None,
+	}
+}
+
+/// Padded strings are limited, so a workflow cannot exhaust the memory of the process.
+const MAX_WIDTH: usize = 1_000_000;
+
+/// Converts a number argument into a character count or index.
+fn count<const N: u32>(arg: &Arg<f64, N>, what: &str) -> Result<usize, Error> {
+	if arg.val < 0.0 || arg.val.fract() != 0.0 || !arg.val.is_finite() {
+		return Err(Error::positional(
+			format!("{} must be a non-negative integer", what),
+			arg.span,
+		));
+	}
+
+	Ok(arg.val as usize)
+}
+
+/// Counts characters like the `[]` operator, not bytes.
+async fn len(string: Arg<String, { id(b"string") }>) -> f64 {
+	string.val.chars().count() as f64
+}
+
+async fn split(
+	string: Arg<String, { id(b"string") }>,
+	separator: Arg<String, { id(b"separator") }>,
+) -> Result<Vec<String>, Error> {
+	if separator.val.is_empty() {
+		return Err(Error::positional("Separator is empty", separator.span));
+	}
+
+	Ok(string
+		.val
+		.split(separator.val.as_str())
+		.map(str::to_owned)
+		.collect())
+}
+
+/// Values which are not strings are joined in their printed form.
+async fn join(
+	values: Arg<Vec<Value>, { id(b"values") }>,
+	separator: Option<Arg<String, { id(b"separator") }>>,
+) -> String {
+	values
+		.val
+		.iter()
+		.map(Value::to_string)
+		.collect::<Vec<_>>()
+		.join(separator.as_ref().map_or("", |separator| &separator.val))
+}
+
+async fn trim(string: Arg<String, { id(b"string") }>) -> String {
+	string.val.trim().to_owned()
+}
+
+async fn upper(string: Arg<String, { id(b"string") }>) -> String {
+	string.val.to_uppercase()
+}
+
+async fn lower(string: Arg<String, { id(b"string") }>) -> String {
+	string.val.to_lowercase()
+}
+
+async fn starts_with(
+	string: Arg<String, { id(b"string") }>,
+	pattern: Arg<String, { id(b"pattern") }>,
+) -> bool {
+	string.val.starts_with(&pattern.val)
+}
+
+async fn ends_with(
+	string: Arg<String, { id(b"string") }>,
+	pattern: Arg<String, { id(b"pattern") }>,
+) -> bool {
+	string.val.ends_with(&pattern.val)
+}
+
+async fn contains(
+	string: Arg<String, { id(b"string") }>,
+	pattern: Arg<String, { id(b"pattern") }>,
+) -> bool {
+	string.val.contains(&pattern.val)
+}
+
+/// Returns the characters from `start` up to, but not including, `end`.
+async fn substring(
+	string: Arg<String, { id(b"string") }>,
+	start: Arg<f64, { id(b"start") }>,
+	end: Option<Arg<f64, { id(b"end") }>>,
+) -> Result<String, Error> {
+	let len = string.val.chars().count();
+	let start_val = count(&start, "Start")?;
+	let end_val = match &end {
+		Some(end) => count(end, "End")?,
+		None => len,
+	};
+	if start_val > end_val || end_val > len {
+		return Err(Error::positional(
+			format!(
+				"Range {}..{} is out of bounds for a string of length {}",
+				start_val, end_val, len
+			),
+			end.as_ref().map_or(start.span, |end| end.span),
+		));
+	}
+
+	Ok(string
+		.val
+		.chars()
+		.skip(start_val)
+		.take(end_val - start_val)
+		.collect())
+}
+
+/// Pads the string to `width` characters, at the start unless `side` is `end`.
+async fn pad(
+	string: Arg<String, { id(b"string") }>,
+	width: Arg<f64, { id(b"width") }>,
+	fill: Option<Arg<String, { id(b"fill") }>>,
+	side: Option<Arg<String, { id(b"side") }>>,
+) -> Result<String, Error> {
+	let width_val = count(&width, "Width")?;
+	if width_val > MAX_WIDTH {
+		return Err(Error::positional(
+			format!(
+				"Width must not exceed {}, but `{}` given",
+				MAX_WIDTH, width.val
+			),
+			width.span,
+		));
+	}
+	let fill_val = match &fill {
+		Some(fill) => {
+			let mut chars = fill.val.chars();
+			match (chars.next(), chars.next()) {
+				(Some(ch), None) => ch,
+				_ => {
+					return Err(Error::positional(
+						"Fill must be a single character",
+						fill.span,
+					));
+				}
+			}
+		}
+		None => ' ',
+	};
+	let at_start = match side.as_ref().map(|side| (side.val.as_str(), side.span)) {
+		None | Some(("start", _)) => true,
+		Some(("end", _)) => false,
+		Some((other, span)) => {
+			return Err(Error::positional(
+				format!("Side `{}` is unknown, expected `start` or `end`", other),
+				span,
+			));
+		}
+	};
+
+	let padding: String = std::iter::repeat_n(
+		fill_val,
+		width_val.saturating_sub(string.val.chars().count()),
+	)
+	.collect();
+	if at_start {
+		Ok(padding + &string.val)
+	} else {
+		Ok(string.val + &padding)
+	}
+}
+
+async fn parse_number(string: Arg<String, { id(b"string") }>) -> Result<f64, Error> {
+	match string.val.trim().parse::<f64>() {
+		Ok(number) if number.is_finite() => Ok(number),
+		_ => Err(Error::positional(
+			format!("`{}` is not a number", string.val),
+			string.span,
+		)),
+	}
+}
+
+/// Numbers are printed with a fixed count of `decimals` if given.
+async fn to_string(
+	value: Arg<Value, { id(b"value") }>,
+	decimals: Option<Arg<f64, { id(b"decimals") }>>,
+	fn_span: Span,
+) -> Result<String, Error> {
+	let Some(decimals) = decimals else {
+		return Ok(value.val.to_string());
+	};
+
+	let decimals_val = count(&decimals, "Decimals")?;
+	match value.val {
+		Value::Number(number) => Ok(format!("{:.*}", decimals_val, number)),
+		other => Err(Error {
+			kind: ErrorKind::InvalidType {
+				msg: format!("decimals given for `{}`", other.get_type()),
+			},
+			span: Some(fn_span),
+		}),
+	}
+}
+
+/// Decoded bytes are only usable as string if they are valid UTF-8.
+pub fn from_utf8(bytes: Vec<u8>, span: Span) -> Result<String, Error> {
+	String::from_utf8(bytes)
+		.map_err(|_| Error::positional("Decoded bytes are not valid UTF-8", span))
+}
@@ -1,7 +1,15 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+
+use ast::Span;
+use chrono::{
+	DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc,
+	format::{Item, StrftimeItems},
+};
+use chrono_tz::Tz;
+use tokio::time::{Instant, MissedTickBehavior};
 
 use crate::{
-	FunctionId, FunctionValue,
+	ChannelId, Environment, Error, FunctionId, FunctionValue, Value,
 	wdl_std::{Arg, get_handler, id},
 };
 
@@ -12,10 +20,262 @@ pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
 
 	match id.id.id.as_str() {
 		"sleep" => Some(get_handler(sleep)),
+		"now" => Some(get_handler(now)),
+		"format" => Some(get_handler(format)),
+		"parse" => Some(get_handler(parse)),
+		"duration" => Some(get_handler(duration)),
+		"since" => Some(get_handler(since)),
+		"deadline" => Some(get_handler(deadline)),
+		"remaining" => Some(get_handler(remaining)),
+		"after" => Some(get_handler(after)),
+		"ticker" => Some(get_handler(ticker)),
 		_ => None,
 	}
 }
 
-pub async fn sleep(ms: Arg<f64, { id(b"ms") }>) {
-	tokio::time::sleep(Duration::from_millis(ms.val as u64)).await; // TODO: fix cast
+type TimeArg = Arg<f64, { id(b"time") }>;
+type FormatArg = Option<Arg<String, { id(b"format") }>>;
+type TimezoneArg = Option<Arg<String, { id(b"timezone") }>>;
+
+/// Fails for negative, NaN and infinite values.
+pub fn millis<const N: u32>(arg: &Arg<f64, N>) -> Result<Duration, Error> {
+	to_duration(arg.val, arg.span)
+}
+
+/// Same as `millis` for numbers not passed as argument of their own, e.g. object fields.
+pub fn to_duration(ms: f64, span: Span) -> Result<Duration, Error> {
+	if !ms.is_finite() || ms < 0.0 {
+		return Err(Error::positional(
+			format!(
+				"Expected a non-negative number of milliseconds, but `{}` given",
+				ms
+			),
+			span,
+		));
+	}
+
+	Duration::try_from_secs_f64(ms / 1000.0)
+		.map_err(|_| Error::positional(format!("Duration `{}` is too long", ms), span))
+}
+
+/// Timestamps are milliseconds since the Unix epoch.
+fn timestamp(arg: &TimeArg) -> Result<DateTime<Utc>, Error> {
+	let time = arg
+		.val
+		.is_finite()
+		.then(|| DateTime::from_timestamp_millis(arg.val.floor() as i64))
+		.flatten();
+
+	time.ok_or_else(|| Error::positional(format!("Invalid timestamp `{}`", arg.val), arg.span))
+}
+
+fn current_millis() -> f64 {
+	Utc::now().timestamp_millis() as f64
+}
+
+fn timezone(arg: Option<&Arg<String, { id(b"timezone") }>>) -> Result<Tz, Error> {
+	let Some(arg) = arg else {
+		return Ok(Tz::UTC);
+	};
+
+	arg.val
+		.parse()
+		.map_err(|_| Error::positional(format!("Unknown timezone `{}`", arg.val), arg.span))
+}
+
+fn format_items(arg: &Arg<String, { id(b"format") }>) -> Result<Vec<Item<'_>>, Error> {
+	let items: Vec<_> = StrftimeItems::new(&arg.val).collect();
+	if items.contains(&Item::Error) {
+		return Err(Error::positional(
+			format!("Invalid format `{}`", arg.val),
+			arg.span,
+		));
+	}
+
+	Ok(items)
+}
+
+pub async fn sleep(ms: Arg<f64, { id(b"ms") }>) -> Result<(), Error> {
+	tokio::time::sleep(millis(&ms)?).await;
+
+	Ok(())
+}
+
+async fn now() -> f64 {
+	current_millis()
+}
+
+/// Formats as RFC 3339 without `format`, otherwise `format` uses the strftime syntax.
+async fn format(time: TimeArg, format: FormatArg, timezone: TimezoneArg) -> Result<String, Error> {
+	let time = timestamp(&time)?.with_timezone(&self::timezone(timezone.as_ref())?);
+
+	let Some(format) = format else {
+		return Ok(time.to_rfc3339_opts(SecondsFormat::Millis, true));
+	};
+
+	Ok(time
+		.format_with_items(format_items(&format)?.into_iter())
+		.to_string())
+}
+
+/// Times without offset are in `timezone`, ambiguous local times resolve to the earlier one.
+async fn parse(
+	string: Arg<String, { id(b"string") }>,
+	format: FormatArg,
+	timezone: TimezoneArg,
+) -> Result<f64, Error> {
+	let tz = self::timezone(timezone.as_ref())?;
+
+	let (with_offset, local) = match &format {
+		Some(format) => {
+			format_items(format)?;
+			(
+				DateTime::parse_from_str(&string.val, &format.val),
+				NaiveDateTime::parse_from_str(&string.val, &format.val).or_else(|_| {
+					NaiveDate::parse_from_str(&string.val, &format.val)
+						.map(|date| date.and_time(Default::default()))
+				}),
+			)
+		}
+		None => (
+			DateTime::parse_from_rfc3339(&string.val),
+			NaiveDateTime::parse_from_str(&string.val, "%Y-%m-%dT%H:%M:%S%.f"),
+		),
+	};
+
+	let time = match (with_offset, local) {
+		(Ok(time), _) => Some(time.timestamp_millis()),
+		(_, Ok(local)) => tz
+			.from_local_datetime(&local)
+			.earliest()
+			.map(|time| time.timestamp_millis()),
+		(Err(err), Err(_)) => {
+			return Err(Error::positional(
+				format!("Cannot parse time `{}`, {}", string.val, err),
+				string.span,
+			));
+		}
+	};
+
+	time.map(|time| time as f64).ok_or_else(|| {
+		Error::positional(
+			format!("Time `{}` does not exist in timezone `{}`", string.val, tz),
+			string.span,
+		)
+	})
+}
+
+/// Sums up the given parts in milliseconds.
+async fn duration(
+	days: Option<Arg<f64, { id(b"days") }>>,
+	hours: Option<Arg<f64, { id(b"hours") }>>,
+	minutes: Option<Arg<f64, { id(b"minutes") }>>,
+	seconds: Option<Arg<f64, { id(b"seconds") }>>,
+	ms: Option<Arg<f64, { id(b"ms") }>>,
+) -> Result<f64, Error> {
+	let parts = [
+		days.map(|arg| (arg.val, arg.span, 86_400_000.0)),
+		hours.map(|arg| (arg.val, arg.span, 3_600_000.0)),
+		minutes.map(|arg| (arg.val, arg.span, 60_000.0)),
+		seconds.map(|arg| (arg.val, arg.span, 1_000.0)),
+		ms.map(|arg| (arg.val, arg.span, 1.0)),
+	];
+
+	parts
+		.into_iter()
+		.flatten()
+		.try_fold(0.0, |sum, (val, span, factor): (f64, Span, f64)| {
+			if !val.is_finite() {
+				return Err(Error::positional(
+					format!("Expected a finite number, but `{}` given", val),
+					span,
+				));
+			}
+
+			Ok(sum + val * factor)
+		})
+}
+
+/// Milliseconds elapsed since `time`, negative if `time` is in the future.
+async fn since(time: TimeArg) -> Result<f64, Error> {
+	timestamp(&time)?;
+
+	Ok(current_millis() - time.val)
+}
+
+/// Timestamp `ms` milliseconds from now.
+async fn deadline(ms: Arg<f64, { id(b"ms") }>) -> Result<f64, Error> {
+	let duration = millis(&ms)?;
+
+	Ok(current_millis() + duration.as_millis() as f64)
+}
+
+/// Milliseconds left until `time`, `0` once it has passed.
+async fn remaining(time: TimeArg) -> Result<f64, Error> {
+	timestamp(&time)?;
+
+	Ok((time.val - current_millis()).max(0.0))
+}
+
+/// The channel receives the current timestamp once after `ms` milliseconds.
+async fn after(ms: Arg<f64, { id(b"ms") }>, env: Arc<Environment>) -> Result<ChannelId, Error> {
+	let duration = millis(&ms)?;
+
+	let (ch_id, ch) = env.create_ch(1).await;
+	let tx = ch.sender();
+	tokio::spawn(async move {
+		tokio::time::sleep(duration).await;
+		let _ = tx.send(Value::Number(current_millis())).await;
+	});
+
+	Ok(ch_id)
+}
+
+/// The channel receives the current timestamp every `ms` milliseconds,
+/// until `count` ticks are sent or the channel is closed.
+/// Ticks missed while the receiver falls behind are skipped.
+async fn ticker(
+	ms: Arg<f64, { id(b"ms") }>,
+	count: Option<Arg<f64, { id(b"count") }>>,
+	env: Arc<Environment>,
+) -> Result<ChannelId, Error> {
+	let period = millis(&ms)?;
+	if period.is_zero() {
+		return Err(Error::positional(
+			"The period of a ticker must be >0",
+			ms.span,
+		));
+	}
+
+	let count = match count {
+		Some(count) if !count.val.is_finite() || count.val.fract() != 0.0 || count.val < 1.0 => {
+			return Err(Error::positional(
+				format!(
+					"The tick count must be a whole number >=1, but `{}` given",
+					count.val
+				),
+				count.span,
+			));
+		}
+		Some(count) => Some(count.val as u64),
+		None => None,
+	};
+
+	let (ch_id, ch) = env.create_ch(1).await;
+	let tx = ch.sender();
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+		interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+		let mut ticks = 0;
+		while count.is_none_or(|count| ticks < count) {
+			interval.tick().await;
+			if tx.send(Value::Number(current_millis())).await.is_err() {
+				break;
+			}
+			ticks += 1;
+		}
+	});
+
+	Ok(ch_id)
 }
@@ -0,0 +1,37 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+
+use crate::{
+	Error, FunctionId, FunctionValue,
+	wdl_std::{Arg, get_handler, id},
+};
+
+use super::string::from_utf8;
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"encode" => Some(get_handler(encode)),
+		"decode" => Some(get_handler(decode)),
+		_ => None,
+	}
+}
+
+/// Characters which are kept as they are, see RFC 3986.
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+	.remove(b'-')
+	.remove(b'_')
+	.remove(b'.')
+	.remove(b'~');
+
+/// Percent-encodes the string to be used as a part of an URL, e.g. a query value.
+async fn encode(string: Arg<String, { id(b"string") }>) -> String {
+	utf8_percent_encode(&string.val, UNRESERVED).to_string()
+}
+
+/// `+` is kept as it is and not decoded as space.
+async fn decode(string: Arg<String, { id(b"string") }>) -> Result<String, Error> {
+	from_utf8(percent_decode_str(&string.val).collect(), string.span)
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use ::uuid::Builder;
+use chrono::Utc;
+use rand::Rng;
+
+use crate::{Environment, FunctionId, FunctionValue, wdl_std::get_handler};
+
+pub fn resolve_id(id: &FunctionId) -> Option<FunctionValue> {
+	if id.scope.len() > 1 {
+		return None;
+	}
+
+	match id.id.id.as_str() {
+		"v4" => Some(get_handler(v4)),
+		"v7" => Some(get_handler(v7)),
+		_ => None,
+	}
+}
+
+/// The random bits are drawn from the seeded generator of the workflow run.
+async fn v4(env: Arc<Environment>) -> String {
+	let bytes = env.random(|rng| rng.r#gen());
+
+	Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Starts with the current time in milliseconds, so IDs of later milliseconds sort after earlier ones.
+async fn v7(env: Arc<Environment>) -> String {
+	let bytes = env.random(|rng| rng.r#gen());
+	let millis = Utc::now().timestamp_millis().max(0) as u64;
+
+	Builder::from_unix_timestamp_millis(millis, &bytes)
+		.into_uuid()
+		.to_string()
+}
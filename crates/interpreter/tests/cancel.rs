@@ -0,0 +1,182 @@
+use std::{collections::HashMap, time::Duration};
+
+use axum::{Json, extract::State, routing::post};
+use serde_json::{Value, json};
+use tokio::{
+	net::TcpListener,
+	sync::mpsc::{self, Receiver, Sender},
+};
+
+use interpreter::{ErrorKind, RunOptions};
+use router::{RouterClientHttp, RouterClientWs, RouterStatus, WsRouterResponse};
+
+/// HTTP router which never finishes an action and reports all cancel requests.
+async fn serve() -> (String, Receiver<Value>) {
+	let (tx, rx) = mpsc::channel(10);
+	let app = axum::Router::new()
+		.route(
+			"/execute",
+			post(|| async {
+				tokio::time::sleep(Duration::from_secs(60)).await;
+				Json(json!({}))
+			}),
+		)
+		.route(
+			"/cancel",
+			post(
+				|State(tx): State<Sender<Value>>, Json(request): Json<Value>| async move {
+					tx.send(request).await.unwrap();
+					Json(json!({}))
+				},
+			),
+		)
+		.with_state(tx);
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(async move { axum::serve(listener, app).await });
+
+	(format!("http://{}", addr), rx)
+}
+
+async fn run(actions: &str, url: &str) -> Result<(), interpreter::Error> {
+	let workflow = parser::get_ast(&format!("actions {{\n{}\n}}", actions)).unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	tokio::spawn(async move { while log_rx.recv().await.is_some() {} });
+
+	interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Http(RouterClientHttp::new(url)),
+		RunOptions::default(),
+		log_tx,
+	)
+	.await
+}
+
+/// Returns all cancel requests received until the router is idle for a while.
+async fn cancels(rx: &mut Receiver<Value>) -> Vec<Value> {
+	let mut cancels = Vec::new();
+	while let Ok(Some(request)) = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await
+	{
+		cancels.push(request);
+	}
+
+	cancels
+}
+
+#[tokio::test]
+async fn cancels_actions_of_aborted_tasks() {
+	let (url, mut rx) = serve().await;
+	let err = run(
+		r#"
+		spawn action::drive(target: { stations: ["a"] });
+		spawn action::drive(target: { stations: ["b"] });
+		time::sleep(200);
+		order::cancel();
+		"#,
+		&url,
+	)
+	.await
+	.unwrap_err();
+	assert!(matches!(err.kind, ErrorKind::OrderCancel), "{:?}", err);
+
+	let mut ids: Vec<_> = cancels(&mut rx)
+		.await
+		.into_iter()
+		.map(|request| request["id"].as_u64().unwrap())
+		.collect();
+	ids.sort();
+
+	// every action is canceled exactly once
+	assert_eq!(ids.len(), 2);
+	assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn cancels_timed_out_action() {
+	let (url, mut rx) = serve().await;
+	let err = run(
+		r#"action::drive(target: { stations: ["a"] }, timeout: 100);"#,
+		&url,
+	)
+	.await
+	.unwrap_err();
+	assert!(
+		matches!(
+			err.kind,
+			ErrorKind::ActionFailed {
+				status: RouterStatus::Timeout
+			}
+		),
+		"{:?}",
+		err
+	);
+
+	assert_eq!(cancels(&mut rx).await.len(), 1);
+}
+
+/// Answers WebSocket router requests like the playground does, the first action only after it was canceled.
+async fn ws_router() -> RouterClientWs {
+	let (tx, mut rx, router) = RouterClientWs::new();
+	tokio::spawn(async move {
+		let first = rx.recv().await.unwrap();
+		let cancel = rx.recv().await.unwrap();
+		assert!(cancel.is_cancel());
+		assert_eq!(cancel.id(), first.id());
+
+		// the late answer must not be taken as result of the next action
+		let late = WsRouterResponse {
+			id: first.id(),
+			result: Ok(RouterStatus::NoStationLeft.into()),
+		};
+		tx.send(late).await.unwrap();
+
+		let second = rx.recv().await.unwrap();
+		let done = WsRouterResponse {
+			id: second.id(),
+			result: Ok(RouterStatus::Done.into()),
+		};
+		tx.send(done).await.unwrap();
+	});
+
+	router
+}
+
+#[tokio::test]
+async fn ws_drops_response_to_canceled_action() {
+	let workflow = parser::get_ast(
+		r#"
+		function timed_out(event) {
+			log::info("timeout");
+		}
+		actions {
+			let first = action::drive(target: { stations: ["a"] }, timeout: 100, events: { timeout: timed_out });
+			log::info(first.status);
+			let second = action::drive(target: { stations: ["b"] });
+			log::info(second.status);
+		}
+		"#,
+	)
+	.unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Ws(ws_router().await),
+		RunOptions::default(),
+		log_tx,
+	)
+	.await
+	.unwrap();
+
+	let mut logs = Vec::new();
+	while let Ok(entry) = log_rx.try_recv() {
+		if entry.user {
+			logs.push(entry.msg);
+		}
+	}
+	assert_eq!(logs, ["timeout", "Timeout", "Done"]);
+}
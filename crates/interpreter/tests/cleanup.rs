@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use axum::{Json, extract::State, routing::post};
+use serde_json::{Value, json};
+use tokio::{
+	net::TcpListener,
+	sync::mpsc::{self, Receiver, Sender},
+};
+
+use interpreter::RunOptions;
+use router::RouterClientHttp;
+
+/// HTTP router reserving vehicle `agv1` and reporting all released vehicles.
+async fn serve() -> (String, Receiver<Value>) {
+	let (tx, rx) = mpsc::channel(10);
+	let app = axum::Router::new()
+		.route(
+			"/reserve",
+			post(|| async { Json(json!({ "id": "agv1", "capabilities": [] })) }),
+		)
+		.route(
+			"/release",
+			post(
+				|State(tx): State<Sender<Value>>, Json(request): Json<Value>| async move {
+					tx.send(request["vehicle"].clone()).await.unwrap();
+					Json(json!({}))
+				},
+			),
+		)
+		.with_state(tx);
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(async move { axum::serve(listener, app).await });
+
+	(format!("http://{}", addr), rx)
+}
+
+async fn run(src_code: &str, url: &str) -> Result<(), interpreter::Error> {
+	let workflow = parser::get_ast(src_code).unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	tokio::spawn(async move { while log_rx.recv().await.is_some() {} });
+
+	interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Http(RouterClientHttp::new(url)),
+		RunOptions::default(),
+		log_tx,
+	)
+	.await
+}
+
+#[tokio::test]
+async fn releases_vehicles_if_declaration_fails() {
+	let (url, mut rx) = serve().await;
+	run(
+		r#"
+		global lifter = fleet::reserve();
+		global broken = 1 / 0;
+		actions {}
+		"#,
+		&url,
+	)
+	.await
+	.unwrap_err();
+
+	assert_eq!(rx.try_recv().unwrap(), "agv1");
+}
+
+#[tokio::test]
+async fn releases_locks_if_declaration_fails() {
+	let (url, _rx) = serve().await;
+	run(
+		r#"
+		global locked = lock::acquire(name: "cleanup");
+		global broken = 1 / 0;
+		actions {}
+		"#,
+		&url,
+	)
+	.await
+	.unwrap_err();
+
+	run(
+		r#"actions { lock::acquire(name: "cleanup", timeout: 100); }"#,
+		&url,
+	)
+	.await
+	.unwrap();
+}
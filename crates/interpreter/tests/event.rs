@@ -0,0 +1,60 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::sync::mpsc;
+
+use interpreter::{RunOptions, Value};
+use router::RouterClientHttp;
+
+/// Runs the statements and returns the messages logged by the workflow.
+async fn run(statements: &str) -> Vec<String> {
+	let workflow = parser::get_ast(&format!("actions {{\n{}\n}}", statements)).unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Http(RouterClientHttp::new("http://127.0.0.1:1")),
+		RunOptions::default(),
+		log_tx,
+	)
+	.await
+	.unwrap();
+
+	let mut logs = Vec::new();
+	while let Ok(entry) = log_rx.try_recv() {
+		if entry.user {
+			logs.push(entry.msg);
+		}
+	}
+
+	logs
+}
+
+/// Sends the event until a workflow receives it.
+async fn send(name: &str, payload: Value) {
+	while interpreter::send_event(name, payload.clone()).await == 0 {
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+}
+
+#[tokio::test]
+async fn delivers_payload() {
+	let (logs, ()) = tokio::join!(
+		run(r#"
+			let event = event::wait("confirmed", timeout: 5000);
+			log::info(event.name);
+			log::info(event.payload);
+			"#),
+		send("confirmed", Value::String("station a".to_owned())),
+	);
+
+	assert_eq!(logs, ["confirmed", "station a"]);
+}
+
+#[tokio::test]
+async fn returns_null_after_timeout() {
+	let logs = run(r#"log::info(event::wait("never", timeout: 50));"#).await;
+
+	assert_eq!(logs, ["null"]);
+	assert_eq!(interpreter::send_event("never", Value::Null).await, 0);
+}
@@ -0,0 +1,139 @@
+// the playground only allows requests to `dummyjson.com`, run with `cargo test -p interpreter`
+#![cfg(not(feature = "playground"))]
+
+use std::{collections::HashMap, time::Duration};
+
+use axum::{Json, routing::get};
+use tokio::{net::TcpListener, sync::mpsc};
+
+use interpreter::{Error, ErrorKind, RunOptions};
+use router::RouterClientHttp;
+
+/// Serves `/json` and `/slow`, which answers after one second.
+async fn serve() -> String {
+	let app = axum::Router::new()
+		.route(
+			"/json",
+			get(|| async { Json(serde_json::json!({ "station": "a" })) })
+				.delete(|| async { "deleted" }),
+		)
+		.route(
+			"/slow",
+			get(|| async {
+				tokio::time::sleep(Duration::from_secs(1)).await;
+				"late"
+			}),
+		);
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(async move { axum::serve(listener, app).await });
+
+	format!("http://{}", addr)
+}
+
+/// Address nothing listens on, so connections are refused.
+async fn closed() -> String {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	drop(listener);
+
+	format!("http://{}", addr)
+}
+
+/// Runs the statements and returns the messages logged by the workflow.
+async fn run(statements: &str, url: &str) -> Result<Vec<String>, Error> {
+	let src_code = format!("global url = \"{}\";\nactions {{\n{}\n}}", url, statements);
+	let workflow = parser::get_ast(&src_code).unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	let ret = interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Http(RouterClientHttp::new("http://127.0.0.1:1")),
+		RunOptions::default(),
+		log_tx,
+	)
+	.await;
+
+	let mut logs = Vec::new();
+	while let Ok(entry) = log_rx.try_recv() {
+		if entry.user {
+			logs.push(entry.msg);
+		}
+	}
+
+	ret.map(|()| logs)
+}
+
+#[tokio::test]
+async fn returns_response() {
+	let url = serve().await;
+	let logs = run(
+		r#"
+		let response = http::get(url + "/json");
+		log::info(response.status);
+		log::info(response.body.station);
+		log::info(response.error);
+		log::info(http::delete(url + "/json").body);
+		"#,
+		&url,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(logs, ["200", "a", "null", "deleted"]);
+}
+
+#[tokio::test]
+async fn connection_refused_sets_error() {
+	let url = closed().await;
+	let logs = run(
+		r#"
+		let responses = [http::get(url), http::post(url, { a: 1 }), http::put(url), http::patch(url), http::delete(url)];
+		let i = 0;
+		while i < 5 {
+			log::info(responses[i].status);
+			log::info(responses[i].error.kind);
+			i = i + 1;
+		}
+		"#,
+		&url,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(logs, ["null", "connect"].repeat(5));
+}
+
+#[tokio::test]
+async fn timeout_sets_error() {
+	let url = serve().await;
+	let logs = run(
+		r#"
+		let response = http::get(url + "/slow", timeout: 100);
+		log::info(response.status);
+		log::info(response.error.kind);
+		"#,
+		&url,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(logs, ["null", "timeout"]);
+}
+
+#[tokio::test]
+async fn rejects_invalid_timeout() {
+	let url = serve().await;
+	for timeout in ["-1", "-0.5"] {
+		let statement = format!("http::get(url + \"/json\", timeout: {});", timeout);
+		let err = run(&statement, &url).await.unwrap_err();
+
+		let ErrorKind::Fatal(msg) = err.kind else {
+			panic!("unexpected error {:?}", err);
+		};
+		assert!(msg.starts_with("Expected a non-negative number"), "{}", msg);
+		assert!(err.span.is_some());
+	}
+}
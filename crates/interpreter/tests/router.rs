@@ -0,0 +1,47 @@
+use std::{collections::HashMap, time::Duration};
+
+use axum::routing::get;
+use tokio::{net::TcpListener, sync::mpsc};
+
+use interpreter::RunOptions;
+use router::RouterClientHttp;
+
+/// HTTP router which never answers the health check.
+async fn serve_hanging() -> String {
+	let app = axum::Router::new().route(
+		"/health",
+		get(|| async {
+			tokio::time::sleep(Duration::from_secs(60)).await;
+			"{}"
+		}),
+	);
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(async move { axum::serve(listener, app).await });
+
+	format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn hanging_router_is_unavailable() {
+	let url = serve_hanging().await;
+	let workflow = parser::get_ast("actions {\nlog::info(router::available());\n}").unwrap();
+
+	let (log_tx, mut log_rx) = mpsc::channel(100);
+	let run = interpreter::run_workflow(
+		workflow,
+		HashMap::new(),
+		interpreter::Router::Http(RouterClientHttp::new(&url)),
+		RunOptions::default(),
+		log_tx,
+	);
+	tokio::time::timeout(Duration::from_secs(10), run)
+		.await
+		.unwrap()
+		.unwrap();
+
+	let log = log_rx.try_recv().unwrap();
+	assert!(log.user);
+	assert_eq!(log.msg, "false");
+}
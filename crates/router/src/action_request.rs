@@ -0,0 +1,156 @@
+use prost_types::{ListValue, Struct, value::Kind};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{RouterError, Target, proto};
+
+/// Physical action executed by the router, e.g. `pickup`, `drop`, `drive`
+/// or a custom action like `charge`.  
+/// The parameters are passed to the router as they are.  
+/// `vehicle` is the ID of a reserved vehicle which must carry out the action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRequest {
+	pub name: String,
+	pub target: Option<Target>,
+	#[serde(default, skip_serializing_if = "Map::is_empty")]
+	pub params: Map<String, Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub vehicle: Option<String>,
+}
+
+impl ActionRequest {
+	pub fn new(name: impl Into<String>, target: Option<Target>) -> Self {
+		Self {
+			name: name.into(),
+			target,
+			params: Map::new(),
+			vehicle: None,
+		}
+	}
+
+	pub fn into_proto(self, action_id: u64) -> proto::ExecuteRequest {
+		proto::ExecuteRequest {
+			name: self.name,
+			target: self.target.map(Target::into),
+			action_id,
+			params: Some(to_struct(self.params)),
+			vehicle: self.vehicle,
+		}
+	}
+}
+
+impl TryFrom<proto::ExecuteRequest> for ActionRequest {
+	type Error = RouterError;
+
+	fn try_from(request: proto::ExecuteRequest) -> Result<Self, Self::Error> {
+		let params = match request.params {
+			Some(params) => from_struct(params).map_err(|err| {
+				RouterError::Protocol(format!("invalid action parameters: {}", err))
+			})?,
+			None => Map::new(),
+		};
+
+		Ok(Self {
+			name: request.name,
+			target: request.target.map(Target::from),
+			params,
+			vehicle: request.vehicle,
+		})
+	}
+}
+
+fn to_struct(map: Map<String, Value>) -> Struct {
+	Struct {
+		fields: map
+			.into_iter()
+			.map(|(name, value)| (name, to_proto_value(value)))
+			.collect(),
+	}
+}
+
+/// Numbers become doubles, as `Struct` has no integer type.
+fn to_proto_value(value: Value) -> prost_types::Value {
+	let kind = match value {
+		Value::Null => Kind::NullValue(0),
+		Value::Bool(val) => Kind::BoolValue(val),
+		Value::Number(val) => Kind::NumberValue(val.as_f64().unwrap_or_default()),
+		Value::String(val) => Kind::StringValue(val),
+		Value::Array(vals) => Kind::ListValue(ListValue {
+			values: vals.into_iter().map(to_proto_value).collect(),
+		}),
+		Value::Object(map) => Kind::StructValue(to_struct(map)),
+	};
+
+	prost_types::Value { kind: Some(kind) }
+}
+
+fn from_struct(params: Struct) -> Result<Map<String, Value>, String> {
+	params
+		.fields
+		.into_iter()
+		.map(|(name, value)| Ok((name, from_proto_value(value)?)))
+		.collect()
+}
+
+/// Whole numbers are converted back to integers.
+fn from_proto_value(value: prost_types::Value) -> Result<Value, String> {
+	Ok(match value.kind {
+		None | Some(Kind::NullValue(_)) => Value::Null,
+		Some(Kind::BoolValue(val)) => Value::Bool(val),
+		Some(Kind::NumberValue(val)) if val.fract() == 0.0 && val.abs() < i64::MAX as f64 => {
+			Value::Number((val as i64).into())
+		}
+		Some(Kind::NumberValue(val)) => Number::from_f64(val)
+			.map(Value::Number)
+			.ok_or_else(|| format!("number `{}` is not finite", val))?,
+		Some(Kind::StringValue(val)) => Value::String(val),
+		Some(Kind::ListValue(list)) => Value::Array(
+			list.values
+				.into_iter()
+				.map(from_proto_value)
+				.collect::<Result<_, _>>()?,
+		),
+		Some(Kind::StructValue(params)) => Value::Object(from_struct(params)?),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn params_round_trip() {
+		let Value::Object(params) = json!({
+			"level": 80,
+			"ratio": 0.5,
+			"mode": "fast",
+			"nested": { "list": [1, true, null] }
+		}) else {
+			unreachable!()
+		};
+		let mut action = ActionRequest::new("charge", None);
+		action.params = params.clone();
+
+		let request = ActionRequest::try_from(action.into_proto(1)).unwrap();
+
+		assert_eq!(request.params, params);
+	}
+
+	#[test]
+	fn rejects_non_finite_params() {
+		let mut request = ActionRequest::new("charge", None).into_proto(1);
+		request.params = Some(Struct {
+			fields: [(
+				"level".to_owned(),
+				prost_types::Value {
+					kind: Some(Kind::NumberValue(f64::NAN)),
+				},
+			)]
+			.into(),
+		});
+
+		assert!(ActionRequest::try_from(request).is_err());
+	}
+}
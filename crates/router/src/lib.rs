@@ -1,15 +1,41 @@
 //! This crate provides dummy router client implementations used in the
 //! interpreter and interface definitions for implementing router servers.  
-//! The client is currently implemented using gRPC and WebSockets for communication.
+//! The client is currently implemented using gRPC, plain HTTP and WebSockets for communication,
+//! vehicles speaking VDA 5050 can be controlled directly over MQTT.
 
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
 
+mod action_request;
+pub use action_request::*;
 mod target;
 pub use target::*;
+mod target_error;
+pub use target_error::*;
 mod router_client_grpc;
 pub use router_client_grpc::*;
+mod router_client_http;
+pub use router_client_http::*;
 mod router_client_ws;
 pub use router_client_ws::*;
+mod router_client_vda5050;
+pub use router_client_vda5050::*;
+mod router_capabilities;
+pub use router_capabilities::*;
+mod router_error;
+pub use router_error::*;
+mod router_health;
+pub use router_health::*;
+mod router_progress;
+pub use router_progress::*;
+mod router_response;
+pub use router_response::*;
+mod router_server_http;
+pub use router_server_http::*;
+mod vehicle;
+pub use vehicle::*;
+
+pub mod vda5050;
 
 pub mod proto {
 	tonic::include_proto!("router");
@@ -23,6 +49,23 @@ pub const URL: &str = "0.0.0.0:3003";
 pub enum RouterStatus {
 	Done,
 	NoStationLeft,
+	PathBlocked,
+	LoadHandlingFailed,
+	BatteryLow,
+	EmergencyStop,
+	Timeout,
+}
+
+impl RouterStatus {
+	pub const ALL: [RouterStatus; 7] = [
+		Self::Done,
+		Self::NoStationLeft,
+		Self::PathBlocked,
+		Self::LoadHandlingFailed,
+		Self::BatteryLow,
+		Self::EmergencyStop,
+		Self::Timeout,
+	];
 }
 
 impl From<proto::RouterStatus> for RouterStatus {
@@ -30,17 +73,59 @@ impl From<proto::RouterStatus> for RouterStatus {
 		match status {
 			proto::RouterStatus::Done => Self::Done,
 			proto::RouterStatus::NoStationLeft => Self::NoStationLeft,
+			proto::RouterStatus::PathBlocked => Self::PathBlocked,
+			proto::RouterStatus::LoadHandlingFailed => Self::LoadHandlingFailed,
+			proto::RouterStatus::BatteryLow => Self::BatteryLow,
+			proto::RouterStatus::EmergencyStop => Self::EmergencyStop,
+			proto::RouterStatus::Timeout => Self::Timeout,
+		}
+	}
+}
+
+impl From<RouterStatus> for proto::RouterStatus {
+	fn from(status: RouterStatus) -> Self {
+		match status {
+			RouterStatus::Done => Self::Done,
+			RouterStatus::NoStationLeft => Self::NoStationLeft,
+			RouterStatus::PathBlocked => Self::PathBlocked,
+			RouterStatus::LoadHandlingFailed => Self::LoadHandlingFailed,
+			RouterStatus::BatteryLow => Self::BatteryLow,
+			RouterStatus::EmergencyStop => Self::EmergencyStop,
+			RouterStatus::Timeout => Self::Timeout,
 		}
 	}
 }
 
+/// Each action is identified by an ID assigned by the client, which can be
+/// used to cancel the action while it is in progress.  
+/// Clients send intermediate updates of an action over `progress`, if the
+/// router reports them. The sender is dropped when the action is finished.  
+/// Actions without a vehicle are carried out by any vehicle chosen by the router,
+/// reserved vehicles are only used for actions naming them until they are released.
 pub trait RouterClient {
+	fn next_action_id(&self) -> u64;
+
+	#[allow(async_fn_in_trait)]
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError>;
+
+	#[allow(async_fn_in_trait)]
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError>;
+
+	#[allow(async_fn_in_trait)]
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError>;
+
 	#[allow(async_fn_in_trait)]
-	async fn pickup(&self, target: Target) -> Option<RouterStatus>; // TODO: return Result instead of Option
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError>;
 
+	/// Fails if the router cannot be reached.
 	#[allow(async_fn_in_trait)]
-	async fn drop(&self, target: Target) -> Option<RouterStatus>; // TODO: return Result instead of Option
+	async fn health(&self) -> Result<RouterHealth, RouterError>;
 
 	#[allow(async_fn_in_trait)]
-	async fn drive(&self, target: Target) -> Option<RouterStatus>; // TODO: return Result instead of Option
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError>;
 }
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{RouterStatus, proto};
+
+/// Features supported by the router.  
+/// `actions` is `None` if the router accepts any action name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterCapabilities {
+	pub actions: Option<Vec<String>>,
+	pub statuses: Vec<RouterStatus>,
+	pub progress: bool,
+	pub reservation: bool,
+}
+
+impl RouterCapabilities {
+	/// Capabilities of routers which only know the action specific RPCs.
+	pub fn legacy() -> Self {
+		Self {
+			actions: Some(vec![
+				"pickup".to_owned(),
+				"drop".to_owned(),
+				"drive".to_owned(),
+			]),
+			statuses: RouterStatus::ALL.to_vec(),
+			progress: false,
+			reservation: false,
+		}
+	}
+
+	pub fn supports(&self, action: &str) -> bool {
+		self.actions
+			.as_ref()
+			.is_none_or(|actions| actions.iter().any(|name| name == action))
+	}
+}
+
+impl From<RouterCapabilities> for proto::CapabilitiesResponse {
+	fn from(capabilities: RouterCapabilities) -> Self {
+		proto::CapabilitiesResponse {
+			actions: capabilities.actions.unwrap_or_default(),
+			statuses: capabilities
+				.statuses
+				.into_iter()
+				.map(|status| proto::RouterStatus::from(status).into())
+				.collect(),
+			progress: capabilities.progress,
+			reservation: capabilities.reservation,
+		}
+	}
+}
+
+impl From<proto::CapabilitiesResponse> for RouterCapabilities {
+	fn from(capabilities: proto::CapabilitiesResponse) -> Self {
+		RouterCapabilities {
+			actions: Some(capabilities.actions).filter(|actions| !actions.is_empty()),
+			// unknown statuses of newer routers are skipped
+			statuses: capabilities
+				.statuses
+				.into_iter()
+				.filter_map(|status| proto::RouterStatus::try_from(status).ok())
+				.map(RouterStatus::from)
+				.collect(),
+			progress: capabilities.progress,
+			reservation: capabilities.reservation,
+		}
+	}
+}
@@ -1,103 +1,181 @@
-use log::error;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::mpsc::Sender;
+use tonic::{Streaming, transport::Channel};
 
 use crate::{
-	RouterStatus, Target,
-	proto::{self, RouterRequest, router_client::RouterClient},
+	ActionRequest, RouterCapabilities, RouterError, RouterHealth, RouterProgress, RouterResponse,
+	Vehicle, VehicleSelector,
+	proto::{
+		CancelRequest, CapabilitiesRequest, HealthRequest, ReleaseRequest, ReserveRequest,
+		RouterRequest, RouterUpdate, router_client::RouterClient, router_update::Update,
+	},
 };
 
-pub struct RouterClientGrpc;
+#[derive(Default)]
+pub struct RouterClientGrpc {
+	action_id: AtomicU64,
+}
 
+impl RouterClientGrpc {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	async fn connect(&self) -> Result<RouterClient<Channel>, RouterError> {
+		RouterClient::connect(String::from("http://") + super::URL)
+			.await
+			.map_err(|err| RouterError::ConnectFailed(err.to_string()))
+	}
+}
+
+// The generic `Execute` RPC is preferred, routers without support for it
+// are served by the action specific RPCs instead.
 impl crate::RouterClient for RouterClientGrpc {
-	async fn pickup(&self, target: Target) -> Option<RouterStatus> {
-		let mut client = match RouterClient::connect(String::from("http://") + super::URL).await {
-			Ok(c) => c,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
+	fn next_action_id(&self) -> u64 {
+		self.action_id.fetch_add(1, Ordering::Relaxed)
+	}
 
-		let request = tonic::Request::new(RouterRequest {
-			target: Some(target.into()),
-		});
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		let mut client = self.connect().await?;
 
-		let response = match client.pickup(request).await {
-			Ok(r) => r,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
+		match client
+			.execute(tonic::Request::new(action.clone().into_proto(action_id)))
+			.await
+		{
+			Ok(response) => receive_updates(response.into_inner(), progress).await,
+			Err(status) if status.code() == tonic::Code::Unimplemented => {
+				execute_legacy(&mut client, action_id, action, progress).await
 			}
-		};
-		let status = match proto::RouterStatus::try_from(response.get_ref().status) {
-			Ok(s) => s,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
+			Err(status) => Err(status.into()),
+		}
+	}
+
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		let mut client = self.connect().await?;
+
+		client
+			.cancel(tonic::Request::new(CancelRequest { action_id }))
+			.await?;
 
-		Some(status.into())
+		Ok(())
 	}
 
-	async fn drop(&self, target: Target) -> Option<RouterStatus> {
-		let mut client = match RouterClient::connect(String::from("http://") + super::URL).await {
-			Ok(c) => c,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		let mut client = self.connect().await?;
 
-		let request = tonic::Request::new(RouterRequest {
-			target: Some(target.into()),
-		});
+		let vehicle = client
+			.reserve(tonic::Request::new(ReserveRequest {
+				selector: Some(selector.into()),
+			}))
+			.await?;
 
-		let response = match client.drop(request).await {
-			Ok(r) => r,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
-		let status = match proto::RouterStatus::try_from(response.get_ref().status) {
-			Ok(s) => s,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
+		Ok(vehicle.into_inner().into())
+	}
 
-		Some(status.into())
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError> {
+		let mut client = self.connect().await?;
+
+		client
+			.release(tonic::Request::new(ReleaseRequest {
+				vehicle: vehicle.to_owned(),
+			}))
+			.await?;
+
+		Ok(())
 	}
 
-	async fn drive(&self, target: Target) -> Option<RouterStatus> {
-		let mut client = match RouterClient::connect(String::from("http://") + super::URL).await {
-			Ok(c) => c,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
+		let mut client = self.connect().await?;
+
+		match client.health(tonic::Request::new(HealthRequest {})).await {
+			Ok(response) => Ok(response.into_inner().into()),
+			// older routers are assumed to be ready once they are reachable
+			Err(status) if status.code() == tonic::Code::Unimplemented => Ok(RouterHealth::ready()),
+			Err(status) => Err(status.into()),
+		}
+	}
 
-		let request = tonic::Request::new(RouterRequest {
-			target: Some(target.into()),
-		});
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
+		let mut client = self.connect().await?;
 
-		let response = match client.drive(request).await {
-			Ok(r) => r,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
-			}
-		};
-		let status = match proto::RouterStatus::try_from(response.get_ref().status) {
-			Ok(s) => s,
-			Err(err) => {
-				error!("{}", err.to_string());
-				return None;
+		match client
+			.capabilities(tonic::Request::new(CapabilitiesRequest {}))
+			.await
+		{
+			Ok(response) => Ok(response.into_inner().into()),
+			Err(status) if status.code() == tonic::Code::Unimplemented => {
+				Ok(RouterCapabilities::legacy())
 			}
-		};
+			Err(status) => Err(status.into()),
+		}
+	}
+}
 
-		Some(status.into())
+/// Executes `pickup`, `drop` and `drive` with their specific RPCs.  
+/// The streaming RPCs are preferred, routers without progress support
+/// are served by the unary RPCs instead.
+async fn execute_legacy(
+	client: &mut RouterClient<Channel>,
+	action_id: u64,
+	action: ActionRequest,
+	progress: Sender<RouterProgress>,
+) -> Result<RouterResponse, RouterError> {
+	let Some(target) = action.target else {
+		return Err(RouterError::RejectedTarget(format!(
+			"action `{}` requires a target",
+			action.name
+		)));
+	};
+	let request = RouterRequest {
+		target: Some(target.into()),
+		action_id,
+		vehicle: action.vehicle,
+	};
+
+	let stream = match action.name.as_str() {
+		"pickup" => client.pickup_stream(request.clone()).await,
+		"drop" => client.drop_stream(request.clone()).await,
+		"drive" => client.drive_stream(request.clone()).await,
+		name => return Err(RouterError::UnsupportedAction(name.to_owned())),
+	};
+
+	match stream {
+		Ok(response) => receive_updates(response.into_inner(), progress).await,
+		Err(status) if status.code() == tonic::Code::Unimplemented => {
+			let response = match action.name.as_str() {
+				"pickup" => client.pickup(request).await?,
+				"drop" => client.drop(request).await?,
+				_ => client.drive(request).await?,
+			};
+
+			response.into_inner().try_into()
+		}
+		Err(status) => Err(status.into()),
+	}
+}
+
+async fn receive_updates(
+	mut stream: Streaming<RouterUpdate>,
+	progress: Sender<RouterProgress>,
+) -> Result<RouterResponse, RouterError> {
+	while let Some(update) = stream.message().await? {
+		match update.update {
+			Some(Update::Progress(p)) => {
+				// the receiver is allowed to ignore progress updates
+				progress.send(p.into()).await.ok();
+			}
+			Some(Update::Response(response)) => return response.try_into(),
+			None => return Err(RouterError::Protocol("empty update received".to_owned())),
+		}
 	}
+
+	Err(RouterError::Protocol(
+		"stream closed without response".to_owned(),
+	))
 }
@@ -0,0 +1,183 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+	ActionRequest, RouterCapabilities, RouterError, RouterHealth, RouterProgress, RouterResponse,
+	Target, Vehicle, VehicleSelector,
+};
+
+/// Body of the `POST /execute` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRouterRequest {
+	pub id: u64,
+	pub action: String,
+	pub target: Option<Target>,
+	#[serde(default, skip_serializing_if = "Map::is_empty")]
+	pub params: Map<String, Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub vehicle: Option<String>,
+}
+
+/// Body of the `POST /cancel` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpCancelRequest {
+	pub id: u64,
+}
+
+/// Body of the `POST /release` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpReleaseRequest {
+	pub vehicle: String,
+}
+
+/// Client for routers exposing a plain JSON API over HTTP.  
+/// Each action is a single `POST` answered after the action finished,
+/// so no progress is reported.
+pub struct RouterClientHttp {
+	url: String,
+	client: reqwest::Client,
+	action_id: AtomicU64,
+}
+
+impl RouterClientHttp {
+	/// `url` is the base URL the endpoints are appended to, e.g. `http://localhost:3004`.
+	pub fn new(url: impl Into<String>) -> Self {
+		Self {
+			url: url.into().trim_end_matches('/').to_owned(),
+			client: reqwest::Client::new(),
+			action_id: AtomicU64::new(0),
+		}
+	}
+
+	async fn post<T: DeserializeOwned>(
+		&self,
+		endpoint: &str,
+		body: &impl Serialize,
+	) -> Result<T, RouterError> {
+		let body =
+			serde_json::to_vec(body).map_err(|err| RouterError::Protocol(err.to_string()))?;
+		let request = self
+			.client
+			.post(format!("{}/{}", self.url, endpoint))
+			.header("content-type", "application/json")
+			.body(body);
+
+		let (status, bytes) = send(request).await?;
+		parse_response(status, &bytes)
+	}
+
+	/// `None` is returned if the router does not provide the endpoint.
+	async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Option<T>, RouterError> {
+		let request = self.client.get(format!("{}/{}", self.url, endpoint));
+
+		let (status, bytes) = send(request).await?;
+		if status == 404 {
+			return Ok(None);
+		}
+		parse_response(status, &bytes).map(Some)
+	}
+}
+
+async fn send(request: reqwest::RequestBuilder) -> Result<(u16, Vec<u8>), RouterError> {
+	let response = request.send().await.map_err(|err| {
+		if err.is_timeout() {
+			RouterError::Timeout
+		} else {
+			RouterError::ConnectFailed(err.to_string())
+		}
+	})?;
+
+	let status = response.status().as_u16();
+	let bytes = response
+		.bytes()
+		.await
+		.map_err(|err| RouterError::Protocol(err.to_string()))?;
+
+	Ok((status, bytes.to_vec()))
+}
+
+fn parse_response<T: DeserializeOwned>(status: u16, body: &[u8]) -> Result<T, RouterError> {
+	if !(200..300).contains(&status) {
+		return Err(error_from_response(status, body));
+	}
+
+	serde_json::from_slice(body).map_err(|err| RouterError::Protocol(err.to_string()))
+}
+
+/// Routers may answer with a serialized `RouterError`,
+/// otherwise the error is derived from the status code.
+fn error_from_response(status: u16, body: &[u8]) -> RouterError {
+	if let Ok(err) = serde_json::from_slice(body) {
+		return err;
+	}
+
+	let msg = String::from_utf8_lossy(body).into_owned();
+	match status {
+		400 | 404 | 422 => RouterError::RejectedTarget(msg),
+		408 | 504 => RouterError::Timeout,
+		409 => RouterError::VehicleUnavailable(msg),
+		500 => RouterError::RobotFault(msg),
+		501 => RouterError::UnsupportedAction(msg),
+		503 => RouterError::ConnectFailed(msg),
+		_ => RouterError::Protocol(format!("unexpected status {}: {}", status, msg)),
+	}
+}
+
+impl crate::RouterClient for RouterClientHttp {
+	fn next_action_id(&self) -> u64 {
+		self.action_id.fetch_add(1, Ordering::Relaxed)
+	}
+
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		_progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		let request = HttpRouterRequest {
+			id: action_id,
+			action: action.name,
+			target: action.target,
+			params: action.params,
+			vehicle: action.vehicle,
+		};
+
+		self.post("execute", &request).await
+	}
+
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		self.post::<Value>("cancel", &HttpCancelRequest { id: action_id })
+			.await
+			.map(|_| ())
+	}
+
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		self.post("reserve", &selector).await
+	}
+
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError> {
+		let request = HttpReleaseRequest {
+			vehicle: vehicle.to_owned(),
+		};
+
+		self.post::<Value>("release", &request).await.map(|_| ())
+	}
+
+	/// Routers without the endpoint are assumed to be ready once they are reachable.
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
+		Ok(self
+			.get("health")
+			.await?
+			.unwrap_or_else(RouterHealth::ready))
+	}
+
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
+		Ok(self
+			.get("capabilities")
+			.await?
+			.unwrap_or_else(RouterCapabilities::legacy))
+	}
+}
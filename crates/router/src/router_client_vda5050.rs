@@ -0,0 +1,900 @@
+use std::{
+	collections::HashMap,
+	sync::{
+		Mutex,
+		atomic::{AtomicU32, AtomicU64, Ordering},
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use serde::Serialize;
+use tokio::sync::{mpsc::Sender, watch};
+
+use crate::{
+	ActionRequest, Coordinate, RouterCapabilities, RouterError, RouterHealth, RouterProgress,
+	RouterResponse, RouterStatus, Target, Vehicle, VehicleSelector,
+	vda5050::{self, Header},
+};
+
+/// Time to wait for the first state message of the vehicle before an order is sent.
+const STATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection details of a single vehicle controlled over VDA 5050.
+#[derive(Debug, Clone)]
+pub struct Vda5050Config {
+	pub host: String,
+	pub port: u16,
+	pub interface_name: String,
+	pub manufacturer: String,
+	pub serial_number: String,
+}
+
+impl Vda5050Config {
+	pub fn new(host: String, port: u16, manufacturer: String, serial_number: String) -> Self {
+		Self {
+			host,
+			port,
+			interface_name: "uagv".to_owned(),
+			manufacturer,
+			serial_number,
+		}
+	}
+
+	/// Returns the MQTT topic of the vehicle, e.g. `uagv/v2/<manufacturer>/<serial>/order`.
+	pub fn topic(&self, topic: &str) -> String {
+		format!(
+			"{}/v2/{}/{}/{}",
+			self.interface_name, self.manufacturer, self.serial_number, topic
+		)
+	}
+}
+
+/// Translates actions into VDA 5050 orders and tracks the state of the vehicle
+/// until the order is finished or failed.
+/// Only one order is sent to the vehicle at a time, concurrent actions are queued.
+pub struct RouterClientVda5050 {
+	config: Vda5050Config,
+	client: AsyncClient,
+	state: watch::Receiver<Result<vda5050::State, RouterError>>,
+	header_id: AtomicU32,
+	action_id: AtomicU64,
+	/// Distinguishes order IDs from earlier runs using the same vehicle.
+	session: u64,
+	order_lock: tokio::sync::Mutex<()>,
+	orders: Mutex<HashMap<u64, String>>,
+	reserved: Mutex<bool>,
+}
+
+impl RouterClientVda5050 {
+	/// Connects to the MQTT broker, must be called inside a tokio runtime.
+	pub fn new(config: Vda5050Config) -> Self {
+		let mut options = MqttOptions::new(
+			format!("wdl-{}", config.serial_number),
+			config.host.clone(),
+			config.port,
+		);
+		options.set_keep_alive(Duration::from_secs(5));
+
+		let (client, event_loop) = AsyncClient::new(options, 10);
+		let (state_sender, state) = watch::channel(Err(RouterError::ConnectFailed(
+			"no state received from vehicle".to_owned(),
+		)));
+
+		tokio::spawn(receive_states(
+			client.clone(),
+			event_loop,
+			config.topic("state"),
+			state_sender,
+		));
+
+		Self {
+			config,
+			client,
+			state,
+			header_id: AtomicU32::new(0),
+			action_id: AtomicU64::new(0),
+			session: now_ms() as u64,
+			order_lock: tokio::sync::Mutex::new(()),
+			orders: Mutex::new(HashMap::new()),
+			reserved: Mutex::new(false),
+		}
+	}
+
+	fn header(&self) -> Header {
+		Header {
+			header_id: self.header_id.fetch_add(1, Ordering::Relaxed),
+			timestamp: vda5050::timestamp(),
+			version: vda5050::VERSION.to_owned(),
+			manufacturer: self.config.manufacturer.clone(),
+			serial_number: self.config.serial_number.clone(),
+		}
+	}
+
+	async fn publish<T: Serialize>(&self, topic: &str, message: &T) -> Result<(), RouterError> {
+		let payload =
+			serde_json::to_vec(message).map_err(|err| RouterError::Protocol(err.to_string()))?;
+
+		self.client
+			.publish(self.config.topic(topic), QoS::AtLeastOnce, false, payload)
+			.await
+			.map_err(|err| RouterError::ConnectFailed(err.to_string()))
+	}
+
+	/// Waits for the first valid state message of the vehicle.
+	async fn current_state(&self) -> Result<vda5050::State, RouterError> {
+		let mut state = self.state.clone();
+		let wait = state.wait_for(|state| state.is_ok());
+
+		match tokio::time::timeout(STATE_TIMEOUT, wait).await {
+			Ok(Ok(state)) => state.clone(),
+			Ok(Err(_)) => Err(RouterError::ConnectFailed(
+				"state receiver stopped".to_owned(),
+			)),
+			Err(_) => self.state.borrow().clone(),
+		}
+	}
+
+	async fn execute_order(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		let started_at_ms = now_ms();
+		if let Some(vehicle) = &action.vehicle
+			&& *vehicle != self.config.serial_number
+		{
+			return Err(RouterError::VehicleUnavailable(format!(
+				"vehicle `{}` is not controlled by this client",
+				vehicle
+			)));
+		}
+
+		let destination = match action.target {
+			Some(target) => match resolve_target(target)? {
+				Some(destination) => Some(destination),
+				None => {
+					return Ok(RouterResponse {
+						vehicle: Some(self.config.serial_number.clone()),
+						started_at_ms: Some(started_at_ms),
+						finished_at_ms: Some(now_ms()),
+						..RouterStatus::NoStationLeft.into()
+					});
+				}
+			},
+			None => None,
+		};
+
+		let _order = self.order_lock.lock().await;
+		let state = self.current_state().await?;
+
+		// actions without target are executed where the vehicle is located
+		let destination = match destination {
+			Some(destination) => destination,
+			None if !state.last_node_id.is_empty() => Destination {
+				node_id: state.last_node_id.clone(),
+				station: None,
+				coordinate: None,
+			},
+			None => {
+				return Err(RouterError::RejectedTarget(format!(
+					"action `{}` requires a target, the vehicle is not located on a node",
+					action.name
+				)));
+			}
+		};
+
+		let order_id = format!("wdl-{}-{}", self.session, action_id);
+		let action = action_type(&action.name).map(|action_type| vda5050::Action {
+			action_type: action_type.to_owned(),
+			action_id: format!("{}-{}", order_id, action_type),
+			blocking_type: "HARD".to_owned(),
+			action_parameters: action
+				.params
+				.into_iter()
+				.map(|(key, value)| vda5050::ActionParameter { key, value })
+				.collect(),
+		});
+		let order = build_order(
+			self.header(),
+			order_id.clone(),
+			&state.last_node_id,
+			&destination,
+			action.clone(),
+		);
+
+		self.orders
+			.lock()
+			.unwrap()
+			.insert(action_id, order_id.clone());
+		let ret = match self.publish("order", &order).await {
+			Ok(()) => {
+				track_order(
+					self.state.clone(),
+					&order,
+					action.as_ref().map(|action| action.action_id.as_str()),
+					progress,
+				)
+				.await
+			}
+			Err(err) => Err(err),
+		};
+		self.orders.lock().unwrap().remove(&action_id);
+
+		let status = ret?;
+		Ok(RouterResponse {
+			status,
+			station: destination.station,
+			coordinate: destination.coordinate,
+			vehicle: Some(self.config.serial_number.clone()),
+			started_at_ms: Some(started_at_ms),
+			finished_at_ms: Some(now_ms()),
+		})
+	}
+}
+
+impl crate::RouterClient for RouterClientVda5050 {
+	fn next_action_id(&self) -> u64 {
+		self.action_id.fetch_add(1, Ordering::Relaxed)
+	}
+
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		self.execute_order(action_id, action, progress).await
+	}
+
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		let Some(order_id) = self.orders.lock().unwrap().get(&action_id).cloned() else {
+			return Ok(());
+		};
+
+		let instant_actions = vda5050::InstantActions {
+			header: self.header(),
+			actions: vec![vda5050::Action {
+				action_type: "cancelOrder".to_owned(),
+				action_id: format!("{}-cancel", order_id),
+				blocking_type: "HARD".to_owned(),
+				action_parameters: Vec::new(),
+			}],
+		};
+
+		self.publish("instantActions", &instant_actions).await
+	}
+
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		let vehicle = Vehicle {
+			id: self.config.serial_number.clone(),
+			type_: None,
+			capabilities: Vec::new(),
+		};
+		if !selector.matches(&vehicle) {
+			return Err(RouterError::VehicleUnavailable(format!(
+				"only vehicle `{}` is controlled by this client",
+				vehicle.id
+			)));
+		}
+
+		let mut reserved = self.reserved.lock().unwrap();
+		if *reserved {
+			return Err(RouterError::VehicleUnavailable(format!(
+				"vehicle `{}` is already reserved",
+				vehicle.id
+			)));
+		}
+		*reserved = true;
+
+		Ok(vehicle)
+	}
+
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError> {
+		if vehicle == self.config.serial_number {
+			*self.reserved.lock().unwrap() = false;
+		}
+
+		Ok(())
+	}
+
+	/// The vehicle is not ready while an emergency stop or a fatal error is active.
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
+		let state = self.current_state().await?;
+
+		if state.safety_state.e_stop != "NONE" {
+			return Ok(RouterHealth {
+				ready: false,
+				message: Some(format!("emergency stop `{}`", state.safety_state.e_stop)),
+			});
+		}
+		if let Some(error) = state
+			.errors
+			.iter()
+			.find(|error| error.error_level == "FATAL")
+		{
+			return Ok(RouterHealth {
+				ready: false,
+				message: Some(format!("fatal error `{}`", error.error_type)),
+			});
+		}
+
+		Ok(RouterHealth::ready())
+	}
+
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
+		Ok(RouterCapabilities {
+			actions: None,
+			statuses: vec![
+				RouterStatus::Done,
+				RouterStatus::PathBlocked,
+				RouterStatus::LoadHandlingFailed,
+				RouterStatus::EmergencyStop,
+				RouterStatus::NoStationLeft,
+			],
+			progress: true,
+			reservation: true,
+		})
+	}
+}
+
+/// Returns the VDA 5050 action type executed at the destination,
+/// `drive` only moves the vehicle.
+fn action_type(name: &str) -> Option<&str> {
+	match name {
+		"pickup" => Some("pick"),
+		"drive" => None,
+		name => Some(name),
+	}
+}
+
+/// Node the vehicle is sent to, either a station known to the vehicle
+/// or a free coordinate.
+struct Destination {
+	node_id: String,
+	station: Option<String>,
+	coordinate: Option<Coordinate>,
+}
+
+/// Picks the first station or coordinate which is not excluded.
+/// Returns `None` if no station is left.
+fn resolve_target(target: Target) -> Result<Option<Destination>, RouterError> {
+	let not = target.not.unwrap_or_default();
+
+	let excluded_stations = not.stations.unwrap_or_default();
+	if let Some(station) = target
+		.stations
+		.unwrap_or_default()
+		.into_iter()
+		.find(|station| !excluded_stations.contains(station))
+	{
+		return Ok(Some(Destination {
+			node_id: station.clone(),
+			station: Some(station),
+			coordinate: None,
+		}));
+	}
+
+	let excluded_coordinates = not.coordinates.unwrap_or_default();
+	if let Some(coordinate) = target
+		.coordinates
+		.unwrap_or_default()
+		.into_iter()
+		.find(|coord| {
+			!excluded_coordinates
+				.iter()
+				.any(|excluded| excluded.x == coord.x && excluded.y == coord.y)
+		}) {
+		return Ok(Some(Destination {
+			node_id: format!("coordinate_{}_{}", coordinate.x, coordinate.y),
+			station: None,
+			coordinate: Some(coordinate),
+		}));
+	}
+
+	if target
+		.stationareas
+		.is_some_and(|stationareas| !stationareas.is_empty())
+	{
+		return Err(RouterError::RejectedTarget(
+			"station areas are not supported by VDA 5050 vehicles".to_owned(),
+		));
+	}
+
+	Ok(None)
+}
+
+/// Builds an order from the last node of the vehicle to the destination,
+/// the action is executed at the destination.
+fn build_order(
+	header: Header,
+	order_id: String,
+	last_node_id: &str,
+	destination: &Destination,
+	action: Option<vda5050::Action>,
+) -> vda5050::Order {
+	let mut nodes = Vec::new();
+	let mut edges = Vec::new();
+
+	let mut sequence_id = 0;
+	if !last_node_id.is_empty() && last_node_id != destination.node_id {
+		nodes.push(vda5050::Node {
+			node_id: last_node_id.to_owned(),
+			sequence_id,
+			released: true,
+			node_position: None,
+			actions: Vec::new(),
+		});
+		edges.push(vda5050::Edge {
+			edge_id: format!("{}-{}", last_node_id, destination.node_id),
+			sequence_id: sequence_id + 1,
+			released: true,
+			start_node_id: last_node_id.to_owned(),
+			end_node_id: destination.node_id.clone(),
+			actions: Vec::new(),
+		});
+		sequence_id += 2;
+	}
+
+	nodes.push(vda5050::Node {
+		node_id: destination.node_id.clone(),
+		sequence_id,
+		released: true,
+		node_position: destination
+			.coordinate
+			.as_ref()
+			.map(|coord| vda5050::NodePosition {
+				x: coord.x,
+				y: coord.y,
+				map_id: String::new(),
+			}),
+		actions: action.into_iter().collect(),
+	});
+
+	vda5050::Order {
+		header,
+		order_id,
+		order_update_id: 0,
+		nodes,
+		edges,
+	}
+}
+
+/// Follows the state messages of the vehicle until the order is done or failed.
+async fn track_order(
+	mut receiver: watch::Receiver<Result<vda5050::State, RouterError>>,
+	order: &vda5050::Order,
+	action_id: Option<&str>,
+	progress: Sender<RouterProgress>,
+) -> Result<RouterStatus, RouterError> {
+	let node_count = order.nodes.len();
+	let mut last_percent = None;
+	loop {
+		let state = receiver.borrow_and_update().clone()?;
+
+		if let Some(outcome) =
+			order_outcome(&state, &order.order_id, order.order_update_id, action_id)
+		{
+			return outcome;
+		}
+
+		if state.order_id == order.order_id {
+			let remaining = state.node_states.len().min(node_count);
+			let percent = ((node_count - remaining) * 100 / node_count) as f64;
+			if last_percent != Some(percent) {
+				last_percent = Some(percent);
+
+				// the receiver is allowed to ignore progress updates
+				progress
+					.send(RouterProgress {
+						vehicle: Some(order.header.serial_number.clone()),
+						eta_ms: None,
+						position: state.agv_position.map(|position| Coordinate {
+							x: position.x,
+							y: position.y,
+						}),
+						percent: Some(percent),
+					})
+					.await
+					.ok();
+			}
+		}
+
+		receiver
+			.changed()
+			.await
+			.map_err(|_| RouterError::ConnectFailed("state receiver stopped".to_owned()))?;
+	}
+}
+
+/// Checks if the given order is finished or failed, returns `None` while it is still running.
+/// E-stops and errors not referencing the order only count once the vehicle acknowledged the order,
+/// before they may be left over from an earlier order.
+fn order_outcome(
+	state: &vda5050::State,
+	order_id: &str,
+	order_update_id: u32,
+	action_id: Option<&str>,
+) -> Option<Result<RouterStatus, RouterError>> {
+	let accepted = state.order_id == order_id && state.order_update_id == order_update_id;
+	if accepted && state.safety_state.e_stop != "NONE" {
+		return Some(Ok(RouterStatus::EmergencyStop));
+	}
+
+	for error in &state.errors {
+		let references_order = error.error_references.iter().any(|reference| {
+			reference.reference_key == "orderId" && reference.reference_value == order_id
+		});
+		let description = error
+			.error_description
+			.clone()
+			.unwrap_or_else(|| error.error_type.clone());
+
+		if error.error_type == "noRouteError" && (references_order || accepted) {
+			return Some(Ok(RouterStatus::PathBlocked));
+		}
+		if references_order {
+			return Some(Err(RouterError::RejectedTarget(description)));
+		}
+		if accepted && error.error_level == "FATAL" {
+			return Some(Err(RouterError::RobotFault(description)));
+		}
+	}
+
+	if !accepted {
+		return None;
+	}
+
+	let action_state = action_id.and_then(|action_id| {
+		state
+			.action_states
+			.iter()
+			.find(|action| action.action_id == action_id)
+	});
+	if action_state.is_some_and(|action| action.action_status == "FAILED") {
+		return Some(Ok(RouterStatus::LoadHandlingFailed));
+	}
+
+	let action_done = match action_id {
+		Some(_) => action_state.is_some_and(|action| action.action_status == "FINISHED"),
+		None => true,
+	};
+	if state.node_states.is_empty() && state.edge_states.is_empty() && action_done {
+		return Some(Ok(RouterStatus::Done));
+	}
+
+	None
+}
+
+/// Drives the MQTT connection and publishes every state message of the vehicle.
+async fn receive_states(
+	client: AsyncClient,
+	mut event_loop: EventLoop,
+	topic: String,
+	sender: watch::Sender<Result<vda5050::State, RouterError>>,
+) {
+	while !sender.is_closed() {
+		match event_loop.poll().await {
+			Ok(Event::Incoming(Packet::ConnAck(_))) => {
+				if let Err(err) = client.try_subscribe(&topic, QoS::AtLeastOnce) {
+					log::error!("Failed to subscribe to `{}`: {}", topic, err);
+				}
+			}
+			Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == topic => {
+				match serde_json::from_slice::<vda5050::State>(&publish.payload) {
+					Ok(state) => {
+						sender.send(Ok(state)).ok();
+					}
+					Err(err) => log::warn!("Invalid state message received: {}", err),
+				}
+			}
+			Ok(_) => {}
+			Err(err) => {
+				sender
+					.send(Err(RouterError::ConnectFailed(err.to_string())))
+					.ok();
+				tokio::time::sleep(Duration::from_secs(1)).await;
+			}
+		}
+	}
+}
+
+fn now_ms() -> f64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|duration| duration.as_millis() as f64)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	const ORDER_ID: &str = "wdl-1-0";
+	const ACTION_ID: &str = "wdl-1-0-pick";
+
+	/// State message as published by the simulated vehicle of `cli vehicle`.
+	fn state(message: serde_json::Value) -> vda5050::State {
+		let mut state = json!({
+			"headerId": 7,
+			"timestamp": "2025-01-01T10:00:00.000Z",
+			"version": "2.0.0",
+			"manufacturer": "wdl",
+			"serialNumber": "agv1",
+			"orderId": ORDER_ID,
+			"orderUpdateId": 0,
+			"lastNodeId": "a",
+			"lastNodeSequenceId": 0,
+			"nodeStates": [],
+			"edgeStates": [],
+			"driving": false,
+			"actionStates": [],
+			"errors": [],
+			"batteryState": { "batteryCharge": 80.0, "charging": false },
+			"safetyState": { "eStop": "NONE", "fieldViolation": false }
+		});
+		for (key, value) in message.as_object().unwrap() {
+			state[key] = value.clone();
+		}
+
+		serde_json::from_value(state).unwrap()
+	}
+
+	fn outcome(message: serde_json::Value) -> Option<Result<RouterStatus, RouterError>> {
+		order_outcome(&state(message), ORDER_ID, 0, Some(ACTION_ID))
+	}
+
+	fn target(target: serde_json::Value) -> Target {
+		serde_json::from_value(target).unwrap()
+	}
+
+	#[test]
+	fn running_order_has_no_outcome() {
+		assert_eq!(
+			outcome(json!({
+				"nodeStates": [{ "nodeId": "b", "sequenceId": 2, "released": true }],
+				"edgeStates": [{ "edgeId": "a-b", "sequenceId": 1, "released": true }],
+				"driving": true,
+				"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "WAITING" }]
+			})),
+			None
+		);
+		// the vehicle did not accept the order yet
+		assert_eq!(outcome(json!({ "orderId": "wdl-1-9" })), None);
+	}
+
+	#[test]
+	fn finished_order_is_done() {
+		assert_eq!(
+			outcome(json!({
+				"lastNodeId": "b",
+				"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "FINISHED" }]
+			})),
+			Some(Ok(RouterStatus::Done))
+		);
+		// orders without action are done once all nodes are traversed
+		assert_eq!(
+			order_outcome(&state(json!({})), ORDER_ID, 0, None),
+			Some(Ok(RouterStatus::Done))
+		);
+		// a finished drive does not finish the action
+		assert_eq!(
+			outcome(json!({
+				"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "RUNNING" }]
+			})),
+			None
+		);
+	}
+
+	#[test]
+	fn failed_action_is_load_handling_failure() {
+		assert_eq!(
+			outcome(json!({
+				"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "FAILED" }]
+			})),
+			Some(Ok(RouterStatus::LoadHandlingFailed))
+		);
+	}
+
+	#[test]
+	fn emergency_stop() {
+		assert_eq!(
+			outcome(json!({
+				"nodeStates": [{ "nodeId": "b", "sequenceId": 2, "released": true }],
+				"safetyState": { "eStop": "MANUAL", "fieldViolation": false }
+			})),
+			Some(Ok(RouterStatus::EmergencyStop))
+		);
+	}
+
+	#[test]
+	fn errors() {
+		let reference = json!([{ "referenceKey": "orderId", "referenceValue": ORDER_ID }]);
+		assert_eq!(
+			outcome(json!({
+				"errors": [{ "errorType": "noRouteError", "errorLevel": "WARNING", "errorReferences": reference }]
+			})),
+			Some(Ok(RouterStatus::PathBlocked))
+		);
+		assert_eq!(
+			outcome(json!({
+				"errors": [{
+					"errorType": "validationError",
+					"errorLevel": "WARNING",
+					"errorDescription": "unknown node `x`",
+					"errorReferences": reference
+				}]
+			})),
+			Some(Err(RouterError::RejectedTarget(
+				"unknown node `x`".to_owned()
+			)))
+		);
+		assert_eq!(
+			outcome(json!({
+				"errors": [{ "errorType": "motorFault", "errorLevel": "FATAL", "errorReferences": [] }]
+			})),
+			Some(Err(RouterError::RobotFault("motorFault".to_owned())))
+		);
+		// errors and e-stops before the order is acknowledged may be left over from an earlier order
+		assert_eq!(
+			outcome(json!({
+				"orderId": "wdl-1-9",
+				"errors": [{ "errorType": "motorFault", "errorLevel": "FATAL", "errorReferences": [] }],
+				"safetyState": { "eStop": "MANUAL", "fieldViolation": false }
+			})),
+			None
+		);
+		// warnings unrelated to the order are ignored
+		assert_eq!(
+			outcome(json!({
+				"nodeStates": [{ "nodeId": "b", "sequenceId": 2, "released": true }],
+				"errors": [{ "errorType": "batteryLow", "errorLevel": "WARNING", "errorReferences": [] }]
+			})),
+			None
+		);
+	}
+
+	/// Replays the state messages while the order is tracked and returns its outcome.
+	async fn replay(messages: Vec<serde_json::Value>) -> Result<RouterStatus, RouterError> {
+		let destination = resolve_target(target(json!({ "stations": ["b"] })))
+			.unwrap()
+			.unwrap();
+		let action = vda5050::Action {
+			action_type: "pick".to_owned(),
+			action_id: ACTION_ID.to_owned(),
+			blocking_type: "HARD".to_owned(),
+			action_parameters: Vec::new(),
+		};
+		let order = build_order(
+			Header::default(),
+			ORDER_ID.to_owned(),
+			"a",
+			&destination,
+			Some(action),
+		);
+
+		let mut messages = messages.into_iter().map(|message| Ok(state(message)));
+		let (sender, receiver) = watch::channel(messages.next().unwrap());
+		let (progress, _progress_rx) = tokio::sync::mpsc::channel(10);
+		let states = async {
+			for state in messages {
+				// let the order be tracked until it waits for the next state
+				tokio::task::yield_now().await;
+				sender.send(state).unwrap();
+			}
+		};
+
+		let (outcome, ()) = tokio::join!(
+			track_order(receiver, &order, Some(ACTION_ID), progress),
+			states
+		);
+		outcome
+	}
+
+	#[tokio::test]
+	async fn stale_errors_do_not_fail_new_order() {
+		let stale = json!({
+			"orderId": "wdl-1-9",
+			"errors": [{ "errorType": "motorFault", "errorLevel": "FATAL", "errorReferences": [] }],
+			"safetyState": { "eStop": "AUTOACK", "fieldViolation": false }
+		});
+		let running = json!({
+			"nodeStates": [{ "nodeId": "b", "sequenceId": 2, "released": true }],
+			"edgeStates": [{ "edgeId": "a-b", "sequenceId": 1, "released": true }],
+			"driving": true,
+			"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "WAITING" }]
+		});
+		let finished = json!({
+			"lastNodeId": "b",
+			"actionStates": [{ "actionId": ACTION_ID, "actionType": "pick", "actionStatus": "FINISHED" }]
+		});
+		assert_eq!(
+			replay(vec![stale.clone(), running.clone(), finished]).await,
+			Ok(RouterStatus::Done)
+		);
+
+		// errors reported after the vehicle acknowledged the order fail it
+		let fault = json!({
+			"nodeStates": [{ "nodeId": "b", "sequenceId": 2, "released": true }],
+			"errors": [{ "errorType": "motorFault", "errorLevel": "FATAL", "errorReferences": [] }]
+		});
+		assert_eq!(
+			replay(vec![stale, running, fault]).await,
+			Err(RouterError::RobotFault("motorFault".to_owned()))
+		);
+	}
+
+	#[test]
+	fn resolves_first_station_not_excluded() {
+		let destination = resolve_target(target(json!({
+			"stations": ["a", "b", "c"],
+			"not": { "stations": ["a"] }
+		})))
+		.unwrap()
+		.unwrap();
+		assert_eq!(destination.node_id, "b");
+		assert_eq!(destination.station.as_deref(), Some("b"));
+
+		let destination = resolve_target(target(json!({
+			"coordinates": [{ "x": 1.0, "y": 2.0 }, { "x": 3.0, "y": 4.0 }],
+			"not": { "coordinates": [{ "x": 1.0, "y": 2.0 }] }
+		})))
+		.unwrap()
+		.unwrap();
+		assert_eq!(destination.node_id, "coordinate_3_4");
+		assert_eq!(destination.coordinate, Some(Coordinate { x: 3.0, y: 4.0 }));
+
+		assert!(
+			resolve_target(target(
+				json!({ "stations": ["a"], "not": { "stations": ["a"] } })
+			))
+			.unwrap()
+			.is_none()
+		);
+		assert!(resolve_target(target(json!({ "stationareas": ["hall"] }))).is_err());
+	}
+
+	#[test]
+	fn order_leads_from_last_node_to_destination() {
+		let destination = resolve_target(target(json!({ "stations": ["b"] })))
+			.unwrap()
+			.unwrap();
+		let action = vda5050::Action {
+			action_type: "pick".to_owned(),
+			action_id: ACTION_ID.to_owned(),
+			blocking_type: "HARD".to_owned(),
+			action_parameters: Vec::new(),
+		};
+
+		let order = build_order(
+			Header::default(),
+			ORDER_ID.to_owned(),
+			"a",
+			&destination,
+			Some(action),
+		);
+		let nodes: Vec<_> = order
+			.nodes
+			.iter()
+			.map(|node| (node.node_id.as_str(), node.sequence_id, node.actions.len()))
+			.collect();
+		assert_eq!(nodes, [("a", 0, 0), ("b", 2, 1)]);
+		assert_eq!(order.edges.len(), 1);
+		assert_eq!(order.edges[0].start_node_id, "a");
+		assert_eq!(order.edges[0].end_node_id, "b");
+
+		// the vehicle already is at the destination
+		let order = build_order(
+			Header::default(),
+			ORDER_ID.to_owned(),
+			"b",
+			&destination,
+			None,
+		);
+		assert_eq!(order.nodes.len(), 1);
+		assert!(order.edges.is_empty());
+	}
+}
@@ -1,26 +1,68 @@
-use std::sync::Arc;
+use std::{
+	collections::HashMap,
+	sync::atomic::{AtomicU64, Ordering},
+};
 
+use log::debug;
 use serde::Serialize;
-use tokio::sync::{
-	Mutex,
-	mpsc::{self, Receiver, Sender},
+use serde_json::{Map, Value};
+use tokio::{
+	select,
+	sync::{
+		Mutex,
+		mpsc::{self, Receiver, Sender},
+		oneshot,
+	},
 };
 
-use crate::{RouterStatus, Target};
+use crate::{
+	ActionRequest, RouterCapabilities, RouterError, RouterHealth, RouterProgress, RouterResponse,
+	RouterStatus, Target, Vehicle, VehicleSelector,
+};
 
+/// `target` is `None` for `Cancel` requests and actions without a target.
 #[derive(Debug, Serialize)]
 pub struct WsRouterRequest {
+	id: u64,
 	action: String,
-	target: Target,
+	target: Option<Target>,
+	#[serde(skip_serializing_if = "Map::is_empty")]
+	params: Map<String, Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	vehicle: Option<String>,
+}
+
+impl WsRouterRequest {
+	pub fn id(&self) -> u64 {
+		self.id
+	}
+
+	pub fn is_cancel(&self) -> bool {
+		self.action == "Cancel"
+	}
+}
+
+/// Answer to the request with the same `id`.
+#[derive(Debug)]
+pub struct WsRouterResponse {
+	pub id: u64,
+	pub result: Result<RouterResponse, RouterError>,
 }
 
+type Pending = oneshot::Sender<Result<RouterResponse, RouterError>>;
+
 pub struct RouterClientWs {
-	receiver: Arc<Mutex<Receiver<RouterStatus>>>,
+	receiver: Mutex<Receiver<WsRouterResponse>>,
+	/// Requests waiting for their response, responses to other ids are dropped,
+	/// e.g. late answers to canceled actions.
+	pending: std::sync::Mutex<HashMap<u64, Pending>>,
 	sender: Sender<WsRouterRequest>,
+	action_id: AtomicU64,
+	reserved: Mutex<bool>,
 }
 
 impl RouterClientWs {
-	pub fn new() -> (Sender<RouterStatus>, Receiver<WsRouterRequest>, Self) {
+	pub fn new() -> (Sender<WsRouterResponse>, Receiver<WsRouterRequest>, Self) {
 		let (tx1, receiver) = mpsc::channel(3);
 		let (sender, rx2) = mpsc::channel(3);
 
@@ -28,47 +70,187 @@ impl RouterClientWs {
 			tx1,
 			rx2,
 			Self {
-				receiver: Arc::new(Mutex::new(receiver)),
+				receiver: Mutex::new(receiver),
+				pending: Default::default(),
 				sender,
+				action_id: AtomicU64::new(0),
+				reserved: Mutex::new(false),
 			},
 		)
 	}
-}
 
-impl crate::RouterClient for RouterClientWs {
-	async fn pickup(&self, target: Target) -> Option<RouterStatus> {
+	async fn send(&self, request: WsRouterRequest) -> Result<(), RouterError> {
 		self.sender
-			.send(WsRouterRequest {
-				action: "Pickup".to_owned(),
-				target,
-			})
+			.send(request)
 			.await
-			.unwrap();
+			.map_err(|_| RouterError::ConnectFailed("request channel closed".to_owned()))
+	}
+
+	/// Whoever holds the receiver hands the responses to the waiting requests
+	/// until its own response arrives.
+	async fn request(&self, id: u64, action: ActionRequest) -> Result<RouterResponse, RouterError> {
+		let (tx, mut rx) = oneshot::channel();
+		self.pending.lock().unwrap().insert(id, tx);
+		let request = WsRouterRequest {
+			id,
+			action: action.name,
+			target: action.target,
+			params: action.params,
+			vehicle: action.vehicle,
+		};
+		if let Err(err) = self.send(request).await {
+			self.pending.lock().unwrap().remove(&id);
+			return Err(err);
+		}
+
+		let mut receiver = select! {
+			result = &mut rx => return result.unwrap_or_else(|_| Err(forgotten(id))),
+			receiver = self.receiver.lock() => receiver,
+		};
+		loop {
+			match rx.try_recv() {
+				Ok(result) => return result,
+				Err(oneshot::error::TryRecvError::Closed) => return Err(forgotten(id)),
+				Err(oneshot::error::TryRecvError::Empty) => (),
+			}
 
-		self.receiver.lock().await.recv().await
+			let Some(response) = receiver.recv().await else {
+				return Err(RouterError::ConnectFailed(
+					"response channel closed".to_owned(),
+				));
+			};
+			let pending = self.pending.lock().unwrap().remove(&response.id);
+			match pending {
+				Some(tx) => {
+					let _ = tx.send(response.result);
+				}
+				None => debug!("Dropped response to unknown action {}", response.id),
+			}
+		}
 	}
+}
 
-	async fn drop(&self, target: Target) -> Option<RouterStatus> {
-		self.sender
-			.send(WsRouterRequest {
-				action: "Drop".to_owned(),
-				target,
-			})
-			.await
-			.ok();
+impl crate::RouterClient for RouterClientWs {
+	fn next_action_id(&self) -> u64 {
+		self.action_id.fetch_add(1, Ordering::Relaxed)
+	}
 
-		self.receiver.lock().await.recv().await
+	async fn execute(
+		&self,
+		action_id: u64,
+		action: ActionRequest,
+		_progress: Sender<RouterProgress>,
+	) -> Result<RouterResponse, RouterError> {
+		self.request(action_id, action).await
 	}
 
-	async fn drive(&self, target: Target) -> Option<RouterStatus> {
-		self.sender
-			.send(WsRouterRequest {
-				action: "Drive".to_owned(),
-				target,
-			})
-			.await
-			.ok();
+	async fn cancel(&self, action_id: u64) -> Result<(), RouterError> {
+		self.pending.lock().unwrap().remove(&action_id);
+		self.send(WsRouterRequest {
+			id: action_id,
+			action: "Cancel".to_owned(),
+			target: None,
+			params: Map::new(),
+			vehicle: None,
+		})
+		.await
+	}
+
+	async fn reserve(&self, selector: VehicleSelector) -> Result<Vehicle, RouterError> {
+		let vehicle = playground_vehicle();
+		if !selector.matches(&vehicle) {
+			return Err(RouterError::VehicleUnavailable(format!(
+				"only vehicle `{}` is simulated",
+				vehicle.id
+			)));
+		}
+
+		let mut reserved = self.reserved.lock().await;
+		if *reserved {
+			return Err(RouterError::VehicleUnavailable(format!(
+				"vehicle `{}` is already reserved",
+				vehicle.id
+			)));
+		}
+		*reserved = true;
+
+		Ok(vehicle)
+	}
+
+	async fn release(&self, vehicle: &str) -> Result<(), RouterError> {
+		if vehicle == playground_vehicle().id {
+			*self.reserved.lock().await = false;
+		}
+
+		Ok(())
+	}
+
+	async fn health(&self) -> Result<RouterHealth, RouterError> {
+		if self.sender.is_closed() {
+			return Err(RouterError::ConnectFailed(
+				"request channel closed".to_owned(),
+			));
+		}
+
+		Ok(RouterHealth::ready())
+	}
+
+	/// The user answers each action in the playground, so every action and status is possible.
+	async fn capabilities(&self) -> Result<RouterCapabilities, RouterError> {
+		Ok(RouterCapabilities {
+			actions: None,
+			statuses: RouterStatus::ALL.to_vec(),
+			progress: false,
+			reservation: true,
+		})
+	}
+}
+
+fn forgotten(id: u64) -> RouterError {
+	RouterError::Protocol(format!("action {} was canceled", id))
+}
+
+/// The playground simulates a single vehicle, which can be reserved once at a time.
+fn playground_vehicle() -> Vehicle {
+	Vehicle {
+		id: "playground".to_owned(),
+		type_: None,
+		capabilities: Vec::new(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn response(id: u64, status: RouterStatus) -> WsRouterResponse {
+		WsRouterResponse {
+			id,
+			result: Ok(status.into()),
+		}
+	}
+
+	#[tokio::test]
+	async fn matches_responses_by_id() {
+		let (tx, mut rx, client) = RouterClientWs::new();
+		let drive = client.request(0, ActionRequest::new("drive", None));
+		let pickup = client.request(1, ActionRequest::new("pickup", None));
+		let answer = async {
+			let first = rx.recv().await.unwrap().id();
+			let second = rx.recv().await.unwrap().id();
+
+			// a stray response, then the answers in reverse order
+			tx.send(response(7, RouterStatus::LoadHandlingFailed))
+				.await
+				.unwrap();
+			tx.send(response(second, RouterStatus::NoStationLeft))
+				.await
+				.unwrap();
+			tx.send(response(first, RouterStatus::Done)).await.unwrap();
+		};
 
-		self.receiver.lock().await.recv().await
+		let (drive, pickup, ()) = tokio::join!(drive, pickup, answer);
+		assert_eq!(drive.unwrap().status, RouterStatus::Done);
+		assert_eq!(pickup.unwrap().status, RouterStatus::NoStationLeft);
 	}
 }
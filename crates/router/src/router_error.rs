@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Reasons why an action could not be executed by the router.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RouterError {
+	/// The router could not be reached.
+	ConnectFailed(String),
+	/// The router did not answer in time.
+	Timeout,
+	/// The router refused the given target.
+	RejectedTarget(String),
+	/// No vehicle matching the request is available.
+	VehicleUnavailable(String),
+	/// The router does not know the requested action.
+	UnsupportedAction(String),
+	/// The router answered with something the client does not understand.
+	Protocol(String),
+	/// The vehicle failed while executing the action.
+	RobotFault(String),
+}
+
+impl Display for RouterError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::ConnectFailed(msg) => write!(f, "connection to router failed: {}", msg),
+			Self::Timeout => write!(f, "router did not respond in time"),
+			Self::RejectedTarget(msg) => write!(f, "target rejected by router: {}", msg),
+			Self::VehicleUnavailable(msg) => write!(f, "no vehicle available: {}", msg),
+			Self::UnsupportedAction(name) => {
+				write!(f, "action `{}` is not supported by the router", name)
+			}
+			Self::Protocol(msg) => write!(f, "invalid router response: {}", msg),
+			Self::RobotFault(msg) => write!(f, "robot fault: {}", msg),
+		}
+	}
+}
+
+impl std::error::Error for RouterError {}
+
+impl From<tonic::Status> for RouterError {
+	fn from(status: tonic::Status) -> Self {
+		let msg = status.message().to_owned();
+		match status.code() {
+			tonic::Code::DeadlineExceeded => Self::Timeout,
+			tonic::Code::Unavailable => Self::ConnectFailed(msg),
+			tonic::Code::InvalidArgument | tonic::Code::NotFound | tonic::Code::OutOfRange => {
+				Self::RejectedTarget(msg)
+			}
+			tonic::Code::Aborted | tonic::Code::FailedPrecondition => Self::RobotFault(msg),
+			tonic::Code::ResourceExhausted => Self::VehicleUnavailable(msg),
+			_ => Self::Protocol(msg),
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+/// State of the router, only a ready router accepts actions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterHealth {
+	pub ready: bool,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+}
+
+impl RouterHealth {
+	pub fn ready() -> Self {
+		Self {
+			ready: true,
+			message: None,
+		}
+	}
+}
+
+impl From<RouterHealth> for proto::HealthResponse {
+	fn from(health: RouterHealth) -> Self {
+		proto::HealthResponse {
+			ready: health.ready,
+			message: health.message,
+		}
+	}
+}
+
+impl From<proto::HealthResponse> for RouterHealth {
+	fn from(health: proto::HealthResponse) -> Self {
+		RouterHealth {
+			ready: health.ready,
+			message: health.message,
+		}
+	}
+}
@@ -0,0 +1,64 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Coordinate, proto};
+
+/// Intermediate state of an action reported by the router while it is executed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouterProgress {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub vehicle: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub eta_ms: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub position: Option<Coordinate>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub percent: Option<f64>,
+}
+
+impl Display for RouterProgress {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut parts = Vec::new();
+		if let Some(vehicle) = &self.vehicle {
+			parts.push(format!("vehicle `{}`", vehicle));
+		}
+		if let Some(percent) = self.percent {
+			parts.push(format!("{}% done", percent));
+		}
+		if let Some(eta_ms) = self.eta_ms {
+			parts.push(format!("ETA {} ms", eta_ms));
+		}
+		if let Some(position) = &self.position {
+			parts.push(format!("position ({}, {})", position.x, position.y));
+		}
+
+		if parts.is_empty() {
+			write!(f, "no details")
+		} else {
+			write!(f, "{}", parts.join(", "))
+		}
+	}
+}
+
+impl From<RouterProgress> for proto::RouterProgress {
+	fn from(progress: RouterProgress) -> Self {
+		proto::RouterProgress {
+			vehicle: progress.vehicle,
+			eta_ms: progress.eta_ms,
+			position: progress.position.map(Coordinate::into),
+			percent: progress.percent,
+		}
+	}
+}
+
+impl From<proto::RouterProgress> for RouterProgress {
+	fn from(progress: proto::RouterProgress) -> Self {
+		RouterProgress {
+			vehicle: progress.vehicle,
+			eta_ms: progress.eta_ms,
+			position: progress.position.map(proto::Coordinate::into),
+			percent: progress.percent,
+		}
+	}
+}
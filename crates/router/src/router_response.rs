@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Coordinate, RouterError, RouterStatus, proto};
+
+/// Result of an action, including details about how it was executed.  
+/// The timestamps are given in milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterResponse {
+	pub status: RouterStatus,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub station: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub coordinate: Option<Coordinate>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub vehicle: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub started_at_ms: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub finished_at_ms: Option<f64>,
+}
+
+impl From<RouterStatus> for RouterResponse {
+	fn from(status: RouterStatus) -> Self {
+		RouterResponse {
+			status,
+			station: None,
+			coordinate: None,
+			vehicle: None,
+			started_at_ms: None,
+			finished_at_ms: None,
+		}
+	}
+}
+
+impl From<RouterResponse> for proto::RouterResponse {
+	fn from(response: RouterResponse) -> Self {
+		proto::RouterResponse {
+			status: proto::RouterStatus::from(response.status).into(),
+			station: response.station,
+			coordinate: response.coordinate.map(Coordinate::into),
+			vehicle: response.vehicle,
+			started_at_ms: response.started_at_ms,
+			finished_at_ms: response.finished_at_ms,
+		}
+	}
+}
+
+impl TryFrom<proto::RouterResponse> for RouterResponse {
+	type Error = RouterError;
+
+	fn try_from(response: proto::RouterResponse) -> Result<Self, Self::Error> {
+		let status = match proto::RouterStatus::try_from(response.status) {
+			Ok(status) => status.into(),
+			Err(err) => return Err(RouterError::Protocol(err.to_string())),
+		};
+
+		Ok(RouterResponse {
+			status,
+			station: response.station,
+			coordinate: response.coordinate.map(proto::Coordinate::into),
+			vehicle: response.vehicle,
+			started_at_ms: response.started_at_ms,
+			finished_at_ms: response.finished_at_ms,
+		})
+	}
+}
@@ -0,0 +1,134 @@
+use std::{future::Future, sync::Arc};
+
+use axum::{
+	Json,
+	extract::State,
+	http::StatusCode,
+	response::{IntoResponse, Response},
+	routing::{get, post},
+};
+use serde_json::{Value, json};
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::{
+	HttpCancelRequest, HttpReleaseRequest, HttpRouterRequest, RouterCapabilities, RouterError,
+	RouterHealth, RouterResponse, Vehicle, VehicleSelector,
+};
+
+/// Router served over HTTP by [`HttpRouterServer`],
+/// the counterpart of the gRPC `Router` trait.
+pub trait HttpRouter: Send + Sync + 'static {
+	/// Executes the action and answers after it finished.
+	fn execute(
+		&self,
+		request: HttpRouterRequest,
+	) -> impl Future<Output = Result<RouterResponse, RouterError>> + Send;
+
+	fn cancel(&self, action_id: u64) -> impl Future<Output = Result<(), RouterError>> + Send;
+
+	fn reserve(
+		&self,
+		selector: VehicleSelector,
+	) -> impl Future<Output = Result<Vehicle, RouterError>> + Send;
+
+	fn release(&self, vehicle: String) -> impl Future<Output = Result<(), RouterError>> + Send;
+
+	fn health(&self) -> impl Future<Output = Result<RouterHealth, RouterError>> + Send {
+		async { Ok(RouterHealth::ready()) }
+	}
+
+	fn capabilities(&self) -> impl Future<Output = Result<RouterCapabilities, RouterError>> + Send {
+		async { Ok(RouterCapabilities::legacy()) }
+	}
+}
+
+/// Serves the endpoints `POST /execute`, `/cancel`, `/reserve` and `/release`
+/// as well as `GET /health` and `/capabilities`, all taking and returning JSON.  
+/// Errors are answered with a status code matching the `RouterError`
+/// and the serialized error as body.
+pub struct HttpRouterServer<T> {
+	inner: Arc<T>,
+}
+
+impl<T: HttpRouter> HttpRouterServer<T> {
+	pub fn new(inner: T) -> Self {
+		Self {
+			inner: Arc::new(inner),
+		}
+	}
+
+	pub fn into_router(self) -> axum::Router {
+		axum::Router::new()
+			.route("/execute", post(execute::<T>))
+			.route("/cancel", post(cancel::<T>))
+			.route("/reserve", post(reserve::<T>))
+			.route("/release", post(release::<T>))
+			.route("/health", get(health::<T>))
+			.route("/capabilities", get(capabilities::<T>))
+			.with_state(self.inner)
+	}
+
+	pub async fn serve(self, addr: impl ToSocketAddrs) -> std::io::Result<()> {
+		let listener = TcpListener::bind(addr).await?;
+		axum::serve(listener, self.into_router()).await
+	}
+}
+
+impl IntoResponse for RouterError {
+	fn into_response(self) -> Response {
+		let status = match self {
+			Self::ConnectFailed(_) => StatusCode::SERVICE_UNAVAILABLE,
+			Self::Timeout => StatusCode::GATEWAY_TIMEOUT,
+			Self::RejectedTarget(_) => StatusCode::BAD_REQUEST,
+			Self::VehicleUnavailable(_) => StatusCode::CONFLICT,
+			Self::UnsupportedAction(_) => StatusCode::NOT_IMPLEMENTED,
+			Self::Protocol(_) => StatusCode::BAD_GATEWAY,
+			Self::RobotFault(_) => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		(status, Json(self)).into_response()
+	}
+}
+
+async fn execute<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+	Json(request): Json<HttpRouterRequest>,
+) -> Result<Json<RouterResponse>, RouterError> {
+	router.execute(request).await.map(Json)
+}
+
+async fn cancel<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+	Json(request): Json<HttpCancelRequest>,
+) -> Result<Json<Value>, RouterError> {
+	router.cancel(request.id).await.map(|_| Json(json!({})))
+}
+
+async fn reserve<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+	Json(selector): Json<VehicleSelector>,
+) -> Result<Json<Vehicle>, RouterError> {
+	router.reserve(selector).await.map(Json)
+}
+
+async fn release<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+	Json(request): Json<HttpReleaseRequest>,
+) -> Result<Json<Value>, RouterError> {
+	router
+		.release(request.vehicle)
+		.await
+		.map(|_| Json(json!({})))
+}
+
+async fn health<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+) -> Result<Json<RouterHealth>, RouterError> {
+	router.health().await.map(Json)
+}
+
+async fn capabilities<T: HttpRouter>(
+	State(router): State<Arc<T>>,
+) -> Result<Json<RouterCapabilities>, RouterError> {
+	router.capabilities().await.map(Json)
+}
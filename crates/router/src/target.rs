@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::proto;
+use site::Site;
+
+use crate::{TargetError, proto};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -11,6 +13,145 @@ pub struct Target {
 	pub not: Option<NotTarget>,
 }
 
+impl Target {
+	/// Removes duplicate entries and replaces empty lists with `None`.
+	pub fn normalize(mut self) -> Self {
+		self.stations = normalize_names(self.stations);
+		self.stationareas = normalize_names(self.stationareas);
+		self.coordinates = normalize_coordinates(self.coordinates);
+		self.not = self.not.map(NotTarget::normalize).filter(|not| {
+			not.stations.is_some() || not.stationareas.is_some() || not.coordinates.is_some()
+		});
+
+		self
+	}
+
+	/// Checks a normalized target, names are additionally looked up in the given site.
+	pub fn validate(&self, site: Option<&Site>) -> Result<(), TargetError> {
+		if self.stations.is_none() && self.stationareas.is_none() && self.coordinates.is_none() {
+			return Err(TargetError::Empty);
+		}
+		if self.coordinates.is_some() && (self.stations.is_some() || self.stationareas.is_some()) {
+			return Err(TargetError::MixedKinds);
+		}
+
+		validate_names(&self.stations, &self.stationareas, site)?;
+		validate_coordinates(&self.coordinates)?;
+		if let Some(not) = &self.not {
+			validate_names(&not.stations, &not.stationareas, site)?;
+			validate_coordinates(&not.coordinates)?;
+
+			// without a site, station areas may contain stations which are not excluded
+			let mut stations = self.stations.clone().unwrap_or_default();
+			let mut excluded = not.stations.clone().unwrap_or_default();
+			if let Some(site) = site {
+				stations.extend(area_stations(&self.stationareas, site));
+				excluded.extend(area_stations(&not.stationareas, site));
+			}
+			let excluded_stations = stations.iter().all(|station| excluded.contains(station));
+			let excluded_coordinates = self.coordinates.as_ref().is_none_or(|coordinates| {
+				coordinates.iter().all(|coordinate| {
+					not.coordinates
+						.as_ref()
+						.is_some_and(|not| not.contains(coordinate))
+				})
+			});
+			if (self.stationareas.is_none() || site.is_some())
+				&& excluded_stations
+				&& excluded_coordinates
+			{
+				return Err(TargetError::AllExcluded);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl NotTarget {
+	fn normalize(mut self) -> Self {
+		self.stations = normalize_names(self.stations);
+		self.stationareas = normalize_names(self.stationareas);
+		self.coordinates = normalize_coordinates(self.coordinates);
+
+		self
+	}
+}
+
+fn normalize_names(names: Option<Vec<String>>) -> Option<Vec<String>> {
+	let mut unique: Vec<String> = Vec::new();
+	for name in names.unwrap_or_default() {
+		if !unique.contains(&name) {
+			unique.push(name);
+		}
+	}
+
+	Some(unique).filter(|names| !names.is_empty())
+}
+
+fn normalize_coordinates(coordinates: Option<Vec<Coordinate>>) -> Option<Vec<Coordinate>> {
+	let mut unique: Vec<Coordinate> = Vec::new();
+	for coordinate in coordinates.unwrap_or_default() {
+		if !unique.contains(&coordinate) {
+			unique.push(coordinate);
+		}
+	}
+
+	Some(unique).filter(|coordinates| !coordinates.is_empty())
+}
+
+/// Names of the site, e.g. `Halle-Süd`, are only required to contain more than whitespace.
+fn is_valid_name(name: &str) -> bool {
+	!name.trim().is_empty()
+}
+
+fn validate_names(
+	stations: &Option<Vec<String>>,
+	stationareas: &Option<Vec<String>>,
+	site: Option<&Site>,
+) -> Result<(), TargetError> {
+	for station in stations.iter().flatten() {
+		if !is_valid_name(station) {
+			return Err(TargetError::InvalidName(station.clone()));
+		}
+		if site.is_some_and(|site| site.station(station).is_none()) {
+			return Err(TargetError::UnknownStation(station.clone()));
+		}
+	}
+	for stationarea in stationareas.iter().flatten() {
+		if !is_valid_name(stationarea) {
+			return Err(TargetError::InvalidName(stationarea.clone()));
+		}
+		if site.is_some_and(|site| site.stationarea(stationarea).is_none()) {
+			return Err(TargetError::UnknownStationarea(stationarea.clone()));
+		}
+	}
+
+	Ok(())
+}
+
+fn area_stations(stationareas: &Option<Vec<String>>, site: &Site) -> Vec<String> {
+	stationareas
+		.iter()
+		.flatten()
+		.filter_map(|name| site.stationarea(name))
+		.flat_map(|area| area.stations.iter().cloned())
+		.collect()
+}
+
+fn validate_coordinates(coordinates: &Option<Vec<Coordinate>>) -> Result<(), TargetError> {
+	for coordinate in coordinates.iter().flatten() {
+		if !coordinate.x.is_finite() || !coordinate.y.is_finite() {
+			return Err(TargetError::NonFiniteCoordinate {
+				x: coordinate.x,
+				y: coordinate.y,
+			});
+		}
+	}
+
+	Ok(())
+}
+
 impl From<Target> for proto::Target {
 	fn from(target: Target) -> Self {
 		proto::Target {
@@ -105,7 +246,7 @@ impl From<proto::NotTarget> for NotTarget {
 	}
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Coordinate {
 	pub x: f64, // TODO: change to u32
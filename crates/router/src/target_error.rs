@@ -0,0 +1,43 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Reasons why a target is rejected before it is sent to the router.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TargetError {
+	/// Neither stations, station areas nor coordinates are given.
+	Empty,
+	/// `not` excludes every station and coordinate of the target.
+	AllExcluded,
+	/// Coordinates cannot be combined with stations or station areas.
+	MixedKinds,
+	/// A coordinate is `NaN` or infinite.
+	NonFiniteCoordinate { x: f64, y: f64 },
+	/// The name is empty or only whitespace.
+	InvalidName(String),
+	/// The station is not part of the site.
+	UnknownStation(String),
+	/// The station area is not part of the site.
+	UnknownStationarea(String),
+}
+
+impl Display for TargetError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Empty => write!(f, "no stations, station areas or coordinates given"),
+			Self::AllExcluded => write!(f, "`not` excludes every station and coordinate"),
+			Self::MixedKinds => write!(
+				f,
+				"coordinates cannot be combined with stations or station areas"
+			),
+			Self::NonFiniteCoordinate { x, y } => {
+				write!(f, "coordinate ({}, {}) is not finite", x, y)
+			}
+			Self::InvalidName(name) => write!(f, "name `{}` is empty", name),
+			Self::UnknownStation(name) => write!(f, "station `{}` is unknown", name),
+			Self::UnknownStationarea(name) => write!(f, "station area `{}` is unknown", name),
+		}
+	}
+}
+
+impl std::error::Error for TargetError {}
@@ -0,0 +1,187 @@
+//! Subset of the VDA 5050 (version 2) messages used to control vehicles over MQTT.
+//! All messages are lenient while deserializing, missing members get default values.
+
+use serde::{Deserialize, Serialize};
+
+pub const VERSION: &str = "2.0.0";
+
+/// Members contained in every message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Header {
+	pub header_id: u32,
+	pub timestamp: String,
+	pub version: String,
+	pub manufacturer: String,
+	pub serial_number: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Order {
+	#[serde(flatten)]
+	pub header: Header,
+	pub order_id: String,
+	pub order_update_id: u32,
+	pub nodes: Vec<Node>,
+	pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Node {
+	pub node_id: String,
+	pub sequence_id: u32,
+	pub released: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub node_position: Option<NodePosition>,
+	pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NodePosition {
+	pub x: f64,
+	pub y: f64,
+	pub map_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Edge {
+	pub edge_id: String,
+	pub sequence_id: u32,
+	pub released: bool,
+	pub start_node_id: String,
+	pub end_node_id: String,
+	pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Action {
+	pub action_type: String,
+	pub action_id: String,
+	pub blocking_type: String,
+	pub action_parameters: Vec<ActionParameter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionParameter {
+	pub key: String,
+	pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InstantActions {
+	#[serde(flatten)]
+	pub header: Header,
+	pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct State {
+	#[serde(flatten)]
+	pub header: Header,
+	pub order_id: String,
+	pub order_update_id: u32,
+	pub last_node_id: String,
+	pub last_node_sequence_id: u32,
+	pub node_states: Vec<NodeState>,
+	pub edge_states: Vec<EdgeState>,
+	pub driving: bool,
+	pub action_states: Vec<ActionState>,
+	pub errors: Vec<StateError>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub agv_position: Option<AgvPosition>,
+	pub battery_state: BatteryState,
+	pub safety_state: SafetyState,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NodeState {
+	pub node_id: String,
+	pub sequence_id: u32,
+	pub released: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EdgeState {
+	pub edge_id: String,
+	pub sequence_id: u32,
+	pub released: bool,
+}
+
+/// `action_status` is one of `WAITING`, `INITIALIZING`, `RUNNING`,
+/// `PAUSED`, `FINISHED` or `FAILED`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionState {
+	pub action_id: String,
+	pub action_type: String,
+	pub action_status: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub result_description: Option<String>,
+}
+
+/// `error_level` is either `WARNING` or `FATAL`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StateError {
+	pub error_type: String,
+	pub error_level: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error_description: Option<String>,
+	pub error_references: Vec<ErrorReference>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ErrorReference {
+	pub reference_key: String,
+	pub reference_value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AgvPosition {
+	pub x: f64,
+	pub y: f64,
+	pub theta: f64,
+	pub map_id: String,
+	pub position_initialized: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BatteryState {
+	pub battery_charge: f64,
+	pub charging: bool,
+}
+
+/// `e_stop` is one of `AUTOACK`, `MANUAL`, `REMOTE` or `NONE`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SafetyState {
+	pub e_stop: String,
+	pub field_violation: bool,
+}
+
+impl Default for SafetyState {
+	fn default() -> Self {
+		Self {
+			e_stop: "NONE".to_owned(),
+			field_violation: false,
+		}
+	}
+}
+
+/// Returns the current time in the ISO 8601 format required by VDA 5050.
+pub fn timestamp() -> String {
+	chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
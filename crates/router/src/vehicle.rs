@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+/// Vehicle reserved for a workflow, actions executed with its ID are
+/// carried out by this vehicle only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vehicle {
+	pub id: String,
+	#[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+	pub type_: Option<String>,
+	#[serde(default)]
+	pub capabilities: Vec<String>,
+}
+
+impl From<Vehicle> for proto::Vehicle {
+	fn from(vehicle: Vehicle) -> Self {
+		proto::Vehicle {
+			id: vehicle.id,
+			r#type: vehicle.type_,
+			capabilities: vehicle.capabilities,
+		}
+	}
+}
+
+impl From<proto::Vehicle> for Vehicle {
+	fn from(vehicle: proto::Vehicle) -> Self {
+		Vehicle {
+			id: vehicle.id,
+			type_: vehicle.r#type,
+			capabilities: vehicle.capabilities,
+		}
+	}
+}
+
+/// Describes which vehicles are suitable, a vehicle must match one of the
+/// given IDs and types and provide all given capabilities.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VehicleSelector {
+	pub ids: Option<Vec<String>>,
+	pub types: Option<Vec<String>>,
+	pub capabilities: Option<Vec<String>>,
+}
+
+impl VehicleSelector {
+	pub fn matches(&self, vehicle: &Vehicle) -> bool {
+		let id_matches = self
+			.ids
+			.as_ref()
+			.is_none_or(|ids| ids.contains(&vehicle.id));
+		let type_matches = self.types.as_ref().is_none_or(|types| {
+			vehicle
+				.type_
+				.as_ref()
+				.is_some_and(|type_| types.contains(type_))
+		});
+		let capabilities_match = self.capabilities.as_ref().is_none_or(|capabilities| {
+			capabilities
+				.iter()
+				.all(|capability| vehicle.capabilities.contains(capability))
+		});
+
+		id_matches && type_matches && capabilities_match
+	}
+}
+
+impl From<VehicleSelector> for proto::VehicleSelector {
+	fn from(selector: VehicleSelector) -> Self {
+		proto::VehicleSelector {
+			ids: selector.ids.unwrap_or_default(),
+			types: selector.types.unwrap_or_default(),
+			capabilities: selector.capabilities.unwrap_or_default(),
+		}
+	}
+}
+
+impl From<proto::VehicleSelector> for VehicleSelector {
+	fn from(selector: proto::VehicleSelector) -> Self {
+		let non_empty = |values: Vec<String>| (!values.is_empty()).then_some(values);
+
+		VehicleSelector {
+			ids: non_empty(selector.ids),
+			types: non_empty(selector.types),
+			capabilities: non_empty(selector.capabilities),
+		}
+	}
+}
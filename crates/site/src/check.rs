@@ -0,0 +1,151 @@
+use ast::{Block, Else, Expression, Literal, Node, Span, Statement, Workflow};
+
+use crate::Site;
+
+/// Hint about a possible problem found without running the workflow.
+#[derive(Debug, Clone)]
+pub struct Warning {
+	pub msg: String,
+	pub span: Span,
+}
+
+/// Looks for station and station area names in literal targets
+/// which are not part of the site.
+/// Every object literal with a `stations` or `stationareas` member is treated as target,
+/// names computed at runtime are not checked.
+pub fn check_workflow(workflow: &Workflow, site: &Site) -> Vec<Warning> {
+	let mut warnings = Vec::new();
+	for global in &workflow.globals {
+		check_expression(&global.val.value, site, &mut warnings);
+	}
+	for function in &workflow.functions {
+		check_block(&function.val.body, site, &mut warnings);
+	}
+	check_block(&workflow.actions.val.block, site, &mut warnings);
+
+	warnings.sort_by_key(|warning| (warning.span.start.line, warning.span.start.column));
+	warnings
+}
+
+fn check_block(block: &Node<Block>, site: &Site, warnings: &mut Vec<Warning>) {
+	for stmt in &block.val.stmts {
+		check_statement(stmt, site, warnings);
+	}
+}
+
+fn check_statement(stmt: &Statement, site: &Site, warnings: &mut Vec<Warning>) {
+	match stmt {
+		Statement::Assignment(stmt) => check_expression(&stmt.val.value, site, warnings),
+		Statement::Block(block) => check_block(block, site, warnings),
+		Statement::Break(_) | Statement::Continue(_) => {}
+		Statement::Expression(expr) => check_expression(expr, site, warnings),
+		Statement::If(stmt) => {
+			let mut if_ = stmt;
+			loop {
+				check_expression(&if_.val.condition, site, warnings);
+				check_block(&if_.val.then, site, warnings);
+				match if_.val.else_.as_deref().map(|else_| &else_.val) {
+					Some(Else::Else(block)) => check_block(block, site, warnings),
+					Some(Else::ElseIf(else_if)) => {
+						if_ = else_if;
+						continue;
+					}
+					None => {}
+				}
+				break;
+			}
+		}
+		Statement::Let(stmt) => check_expression(&stmt.val.value, site, warnings),
+		Statement::Return(stmt) => {
+			if let Some(value) = &stmt.val.value {
+				check_expression(value, site, warnings);
+			}
+		}
+		Statement::Send(stmt) => {
+			check_expression(&stmt.val.ch, site, warnings);
+			check_expression(&stmt.val.value, site, warnings);
+		}
+		Statement::While(stmt) => {
+			check_expression(&stmt.val.condition, site, warnings);
+			check_block(&stmt.val.do_, site, warnings);
+		}
+	}
+}
+
+fn check_expression(expr: &Expression, site: &Site, warnings: &mut Vec<Warning>) {
+	match expr {
+		Expression::Array(expr) => {
+			for value in &expr.val.values {
+				check_expression(value, site, warnings);
+			}
+		}
+		Expression::Binary(expr) => {
+			check_expression(&expr.val.left, site, warnings);
+			check_expression(&expr.val.right, site, warnings);
+		}
+		Expression::Call(expr) => {
+			check_expression(&expr.val.function, site, warnings);
+			for arg in &expr.val.args {
+				check_expression(&arg.val.val, site, warnings);
+			}
+		}
+		Expression::Group(expr) => check_expression(&expr.val.expression, site, warnings),
+		Expression::Literal(_) | Expression::Variable(_) => {}
+		Expression::Logic(expr) => {
+			check_expression(&expr.val.left, site, warnings);
+			check_expression(&expr.val.right, site, warnings);
+		}
+		Expression::Member(expr) => check_expression(&expr.val.object, site, warnings),
+		Expression::Object(expr) => {
+			if let Some(stations) = expr.val.values.get("stations") {
+				check_names(
+					stations,
+					"Station",
+					|name| site.station(name).is_some(),
+					warnings,
+				);
+			}
+			if let Some(stationareas) = expr.val.values.get("stationareas") {
+				check_names(
+					stationareas,
+					"Station area",
+					|name| site.stationarea(name).is_some(),
+					warnings,
+				);
+			}
+			for value in expr.val.values.values() {
+				check_expression(value, site, warnings);
+			}
+		}
+		Expression::Offset(expr) => {
+			check_expression(&expr.val.value, site, warnings);
+			check_expression(&expr.val.offset, site, warnings);
+		}
+		Expression::Spawn(expr) => check_expression(&expr.val.expr, site, warnings),
+		Expression::Unary(expr) => check_expression(&expr.val.right, site, warnings),
+	}
+}
+
+fn check_names(
+	names: &Expression,
+	kind: &str,
+	is_known: impl Fn(&str) -> bool,
+	warnings: &mut Vec<Warning>,
+) {
+	let Expression::Array(names) = names else {
+		return;
+	};
+
+	for name in &names.val.values {
+		if let Expression::Literal(Node {
+			span,
+			val: Literal::String(name),
+		}) = name && !is_known(name)
+		{
+			warnings.push(Warning {
+				msg: format!("{} `{}` is not part of the site!", kind, name),
+				span: *span,
+			});
+		}
+	}
+}
@@ -0,0 +1,10 @@
+//! This crate provides the map of a site, consisting of stations with their coordinates,
+//! station areas grouping stations and paths connecting them.  
+//! It is the source of truth for station names used in workflows.
+
+mod site;
+pub use site::*;
+mod site_error;
+pub use site_error::*;
+mod check;
+pub use check::*;
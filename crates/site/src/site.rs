@@ -0,0 +1,186 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::SiteError;
+
+/// Map of a site, loaded from a JSON file like
+/// `{ "stations": [{ "name": "a", "x": 0, "y": 0 }], "stationareas": [{ "name": "hall", "stations": ["a"] }], "paths": [] }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Site {
+	#[serde(default)]
+	pub stations: Vec<Station>,
+	#[serde(default)]
+	pub stationareas: Vec<Stationarea>,
+	#[serde(default)]
+	pub paths: Vec<Path>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Station {
+	pub name: String,
+	pub x: f64,
+	pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stationarea {
+	pub name: String,
+	pub stations: Vec<String>,
+}
+
+/// Straight connection between two stations, which can be used in both directions
+/// unless it is `oneway`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Path {
+	pub from: String,
+	pub to: String,
+	#[serde(default)]
+	pub oneway: bool,
+}
+
+impl Site {
+	/// Parses the site map and checks that all referenced stations are defined.
+	pub fn from_json(json: &str) -> Result<Self, SiteError> {
+		let site: Site =
+			serde_json::from_str(json).map_err(|err| SiteError::Json(err.to_string()))?;
+
+		let mut names = HashSet::new();
+		for name in site
+			.stations
+			.iter()
+			.map(|station| &station.name)
+			.chain(site.stationareas.iter().map(|area| &area.name))
+		{
+			if !names.insert(name) {
+				return Err(SiteError::DuplicateName(name.clone()));
+			}
+		}
+
+		let referenced = site
+			.stationareas
+			.iter()
+			.flat_map(|area| area.stations.iter())
+			.chain(site.paths.iter().flat_map(|path| [&path.from, &path.to]));
+		for name in referenced {
+			if site.station(name).is_none() {
+				return Err(SiteError::UnknownStation(name.clone()));
+			}
+		}
+
+		Ok(site)
+	}
+
+	pub fn station(&self, name: &str) -> Option<&Station> {
+		self.stations.iter().find(|station| station.name == name)
+	}
+
+	pub fn stationarea(&self, name: &str) -> Option<&Stationarea> {
+		self.stationareas.iter().find(|area| area.name == name)
+	}
+
+	/// Returns the stations of the given area or all stations of the site.
+	pub fn stations_in(&self, area: Option<&str>) -> Option<Vec<&Station>> {
+		let Some(area) = area else {
+			return Some(self.stations.iter().collect());
+		};
+
+		let area = self.stationarea(area)?;
+		Some(
+			area.stations
+				.iter()
+				.filter_map(|name| self.station(name))
+				.collect(),
+		)
+	}
+
+	/// Returns the station of the given area or site closest to the point,
+	/// measured in a straight line.
+	pub fn nearest(&self, x: f64, y: f64, area: Option<&str>) -> Option<&Station> {
+		self.stations_in(area)?
+			.into_iter()
+			.map(|station| (station, (station.x - x).hypot(station.y - y)))
+			.min_by(|(_, a), (_, b)| a.total_cmp(b))
+			.map(|(station, _)| station)
+	}
+
+	/// Returns the length of the shortest route between both stations.
+	pub fn distance(&self, from: &str, to: &str) -> Option<f64> {
+		let route = self.route(from, to)?;
+
+		Some(route.windows(2).map(|leg| leg[0].distance_to(leg[1])).sum())
+	}
+
+	/// Returns the stations along the shortest route, including the start and end station.
+	/// Sites without paths allow driving straight between all stations.
+	pub fn route(&self, from: &str, to: &str) -> Option<Vec<&Station>> {
+		let start = self.station(from)?;
+		let end = self.station(to)?;
+		if from == to {
+			return Some(vec![start]);
+		}
+		if self.paths.is_empty() {
+			return Some(vec![start, end]);
+		}
+
+		let mut neighbors: HashMap<&str, Vec<&Station>> = HashMap::new();
+		for path in &self.paths {
+			let (Some(a), Some(b)) = (self.station(&path.from), self.station(&path.to)) else {
+				continue;
+			};
+			neighbors.entry(&a.name).or_default().push(b);
+			if !path.oneway {
+				neighbors.entry(&b.name).or_default().push(a);
+			}
+		}
+
+		// Dijkstra, the number of stations is small enough for a linear search
+		let mut distances: HashMap<&str, f64> = HashMap::from([(from, 0.0)]);
+		let mut previous: HashMap<&str, &Station> = HashMap::new();
+		let mut visited = HashSet::new();
+		while let Some((current, distance)) = distances
+			.iter()
+			.filter(|(name, _)| !visited.contains(*name))
+			.min_by(|(_, a), (_, b)| a.total_cmp(b))
+			.map(|(name, distance)| (*name, *distance))
+		{
+			if current == to {
+				break;
+			}
+			visited.insert(current);
+
+			let current_station = self.station(current)?;
+			for next in neighbors.get(current).into_iter().flatten() {
+				let next_distance = distance + current_station.distance_to(next);
+				if distances
+					.get(next.name.as_str())
+					.is_none_or(|known| next_distance < *known)
+				{
+					distances.insert(&next.name, next_distance);
+					previous.insert(&next.name, current_station);
+				}
+			}
+		}
+
+		let mut route = vec![end];
+		let mut current = to;
+		while current != from {
+			let station = previous.get(current)?;
+			route.push(station);
+			current = &station.name;
+		}
+		route.reverse();
+
+		Some(route)
+	}
+}
+
+impl Station {
+	pub fn distance_to(&self, other: &Station) -> f64 {
+		(self.x - other.x).hypot(self.y - other.y)
+	}
+}
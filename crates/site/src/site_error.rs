@@ -0,0 +1,24 @@
+use std::fmt::Display;
+
+/// Reasons why a site map is rejected while loading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiteError {
+	/// The map file is no valid JSON or has an unexpected structure.
+	Json(String),
+	/// A station or station area name is used more than once.
+	DuplicateName(String),
+	/// A station area or path references a station which is not defined.
+	UnknownStation(String),
+}
+
+impl Display for SiteError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Json(msg) => write!(f, "invalid site map: {}", msg),
+			Self::DuplicateName(name) => write!(f, "name `{}` is defined more than once", name),
+			Self::UnknownStation(name) => write!(f, "station `{}` is not defined", name),
+		}
+	}
+}
+
+impl std::error::Error for SiteError {}
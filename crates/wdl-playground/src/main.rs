@@ -6,12 +6,13 @@ use std::{collections::HashMap, time::Duration};
 
 use axum::{Router, http::Method};
 use log::info;
-use serde_json::{Value, json};
+use serde::Deserialize;
+use serde_json::Value;
 use socketioxide::{
-	SocketIo,
+	AckError, SocketIo,
 	extract::{Data, SocketRef},
 };
-use tokio::{select, sync::mpsc};
+use tokio::{select, sync::mpsc, task::JoinSet};
 use tower_http::{
 	compression::CompressionLayer,
 	cors::{Any, CorsLayer},
@@ -19,8 +20,10 @@ use tower_http::{
 };
 
 use format::ColorMode;
-use interpreter::LogEntry;
-use router::{RouterClientWs, RouterStatus};
+use interpreter::{LogEntry, RunOptions};
+use router::{
+	RouterClientWs, RouterError, RouterResponse, RouterStatus, WsRouterRequest, WsRouterResponse,
+};
 
 #[shuttle_runtime::main]
 async fn main() -> shuttle_axum::ShuttleAxum {
@@ -70,51 +73,28 @@ async fn run_workflow(socket: SocketRef, Data(src_code): Data<String>) {
 
 	let async_socket = socket.clone();
 	tokio::spawn(async move {
+		// requests wait for their answer in parallel, so a cancel is not stuck behind them
+		let mut requests = JoinSet::new();
 		loop {
 			select! {
 				_ = exit_receiver.recv() => {
 					return;
 				}
 				request = receiver.recv() => {
-					if request.is_none() {
+					let Some(request) = request else {
 						return;
-					}
-					match async_socket
-						.timeout(Duration::from_secs(600))
-						.emit_with_ack::<_, Vec<String>>("router_request", &request)
-						.unwrap()
-						.await
-					{
-						Ok(ack) => match ack[0].as_str() {
-							"Done" => sender.send(RouterStatus::Done).await.unwrap(),
-							"NoStationLeft" => sender.send(RouterStatus::NoStationLeft).await.unwrap(),
-							status => {
-								async_socket.emit(
-									"error",
-									&json!([
-										{
-											"title": format!("Internal error, received invalid router status `{}`!", status)
-										}
-									])
-								).ok();
-								return;
-							}
-						},
-						Err(err) => {
-							async_socket
-								.emit(
-									"error",
-									&json!([
-										{
-											"title": format!("Router error `{}`!", err)
-										}
-									])
-								)
-								.ok();
-							return;
-						}
 					};
+					if request.is_cancel() {
+						async_socket.emit("router_cancel", &request).ok();
+						continue;
+					}
+					let socket = async_socket.clone();
+					let sender = sender.clone();
+					requests.spawn(async move {
+						sender.send(ask_user(&socket, &request).await).await.ok();
+					});
 				}
+				Some(_) = requests.join_next() => {}
 			}
 		}
 	});
@@ -138,6 +118,7 @@ async fn run_workflow(socket: SocketRef, Data(src_code): Data<String>) {
 		ast,
 		HashMap::new(),
 		interpreter::Router::Ws(router),
+		RunOptions::default(),
 		log_sender,
 	)
 	.await;
@@ -162,6 +143,42 @@ async fn run_workflow(socket: SocketRef, Data(src_code): Data<String>) {
 	}
 }
 
+/// Answer of the user to the router request with the same `id`.
+#[derive(Deserialize)]
+struct RouterAck {
+	id: u64,
+	status: String,
+}
+
+async fn ask_user(socket: &SocketRef, request: &WsRouterRequest) -> WsRouterResponse {
+	let ack = socket
+		.timeout(Duration::from_secs(600))
+		.emit_with_ack::<_, RouterAck>("router_request", request)
+		.unwrap()
+		.await;
+
+	match ack {
+		Ok(ack) => WsRouterResponse {
+			id: ack.id,
+			result: serde_json::from_value::<RouterStatus>(Value::String(ack.status.clone()))
+				.map(RouterResponse::from)
+				.map_err(|_| {
+					RouterError::Protocol(format!(
+						"received invalid router status `{}`",
+						ack.status
+					))
+				}),
+		},
+		Err(err) => WsRouterResponse {
+			id: request.id(),
+			result: Err(match err {
+				AckError::Timeout => RouterError::Timeout,
+				err => RouterError::ConnectFailed(err.to_string()),
+			}),
+		},
+	}
+}
+
 /// `len` must be >= 3
 fn truncate(s: String, len: usize) -> String {
 	if s.chars().count() <= len {
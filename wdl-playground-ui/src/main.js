@@ -41,6 +41,8 @@ RUN_BTN.addEventListener("click", async (_event) => {
 
 	socket.on("router_request", Router.set_request);
 
+	socket.on("router_cancel", Router.cancel_request);
+
 	socket.on("error", (/** @type {WdlError[]} errors */ errors) => {
 		close_socket();
 		display_errors(errors);
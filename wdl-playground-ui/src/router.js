@@ -13,29 +13,36 @@ const TARGET_AREA = document.getElementById("target-area");
  */
 let response_callback = null;
 
+/**
+ * ID of the displayed request, sent back with the router status.
+ *
+ * @type {number|null}
+ */
+let request_id = null;
+
 /**
  * Displays a new request.
  *
- * @param {{action: "Pickup"|"Drop"|"Drive", target: any}} request
+ * @param {{id: number, action: string, target: any, params?: any}} request
  * @param {ResponseCallback} callback
  * @returns {void}
  */
 export function set_request(request, callback) {
 	response_callback = callback;
+	request_id = request.id;
 
 	const action = request.action;
 	ACTION_TEXT.innerText = action.charAt(0).toUpperCase() + action.slice(1);
-	if (action === "Pickup") {
+	if (action === "pickup") {
 		ACTION_TEXT.innerText += " from:";
-	} else if (["Drop", "Drive"].includes(action)) {
+	} else if (["drop", "drive"].includes(action)) {
 		ACTION_TEXT.innerText += " to:";
 	} else {
-		Output.add_error(`Received invalid action type \`${action}\`!`);
-		throw `Received invalid action type \`${action}\`!`;
+		ACTION_TEXT.innerText = `Execute \`${action}\`${request.target ? " at:" : ""}`;
 	}
 
 	TARGET_AREA.innerText = JSON.stringify(
-		request.target,
+		{ ...request.target, ...(request.params && { params: request.params }) },
 		(_key, value) => (value !== null ? value : undefined),
 		4
 	);
@@ -44,12 +51,17 @@ export function set_request(request, callback) {
 }
 
 /**
- * Cancels the current request.
+ * Cancels the request if it is still displayed.
  *
+ * @param {{id: number}} request
  * @returns {void}
  */
-export function cancel_request() {
+export function cancel_request(request) {
+	if (request.id !== request_id) {
+		return;
+	}
 	response_callback = null;
+	request_id = null;
 	hide_request();
 }
 
@@ -103,8 +115,9 @@ function send_status(status) {
 		Output.add_error("Failed to send router status!");
 		throw "Router response callback not set!";
 	}
-	response_callback([status]);
+	response_callback({ id: request_id, status });
 	response_callback = null;
+	request_id = null;
 }
 
 /**
@@ -26,7 +26,7 @@
  * ResponseCallback
  *
  * @callback ResponseCallback
- * @param {"Done"|"NoStationLeft"} status
+ * @param {{id: number, status: "Done"|"NoStationLeft"}} ack
  * @returns {void}
  */
 